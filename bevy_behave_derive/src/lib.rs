@@ -0,0 +1,98 @@
+//! Derive macros for `bevy_behave`, enabled with its `derive` feature.
+//!
+//! See `BehaveTask` and `BehaveCondition` in `bevy_behave` for the traits these implement.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{DeriveInput, Ident, Type, parse_macro_input};
+
+/// Implements `BehaveTask` for a task component, by calling one of its methods each frame.
+///
+/// The method defaults to `update`, and can be changed with `#[behave(method = name)]`.
+/// It takes `&mut self` and the `&BehaveCtx`, and returns `Option<bool>`: `None` while the task
+/// is still running, or `Some(success)` to report the result.
+///
+/// Add `#[behave(param = ...)]` to also pass a system param, eg `Res<'static, Time>`, which the
+/// method takes as `&mut Res<Time>`.
+///
+/// Register the task with `app.add_behave_task::<T>()`.
+#[proc_macro_derive(BehaveTask, attributes(behave))]
+pub fn derive_behave_task(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(
+        &input,
+        quote!(BehaveTask),
+        quote!(poll_task),
+        quote!(&mut self),
+        "update",
+    )
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+/// Implements `BehaveCondition` for a trigger type, by calling one of its methods when the
+/// trigger fires.
+///
+/// The method defaults to `check`, and can be changed with `#[behave(method = name)]`.
+/// It takes `&self` and the `&BehaveCtx`, and returns `Option<bool>`: `Some(success)` to report
+/// the result, or `None` to leave the trigger waiting for a result reported some other way.
+///
+/// Add `#[behave(param = ...)]` to also pass a system param, as with `BehaveTask`.
+///
+/// Register the condition with `app.add_behave_condition::<T>()`.
+#[proc_macro_derive(BehaveCondition, attributes(behave))]
+pub fn derive_behave_condition(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(
+        &input,
+        quote!(BehaveCondition),
+        quote!(check_condition),
+        quote!(&self),
+        "check",
+    )
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+fn expand(
+    input: &DeriveInput,
+    trait_name: TokenStream2,
+    trait_method: TokenStream2,
+    receiver: TokenStream2,
+    default_method: &str,
+) -> syn::Result<TokenStream2> {
+    let mut method = Ident::new(default_method, proc_macro2::Span::call_site());
+    let mut param: Option<Type> = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("behave")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("method") {
+                method = meta.value()?.parse()?;
+                Ok(())
+            } else if meta.path.is_ident("param") {
+                param = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `method` or `param`"))
+            }
+        })?;
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (param_ty, call) = match &param {
+        Some(param) => (quote!(#param), quote!(Self::#method(self, ctx, param))),
+        None => (quote!(()), quote!(Self::#method(self, ctx))),
+    };
+    Ok(quote! {
+        impl #impl_generics ::bevy_behave::prelude::#trait_name for #name #ty_generics #where_clause {
+            type Param = #param_ty;
+            fn #trait_method(
+                #receiver,
+                ctx: &::bevy_behave::prelude::BehaveCtx,
+                #[allow(unused_variables)]
+                param: &mut ::bevy_behave::__private::SystemParamItem<Self::Param>,
+            ) -> ::core::option::Option<bool> {
+                #call
+            }
+        }
+    })
+}
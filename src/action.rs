@@ -0,0 +1,152 @@
+//! Stateful actions run inline by the tree, without spawning a task entity.
+use crate::{
+    BehaveNode,
+    ctx::BehaveCtx,
+    prelude::{BehaveFailureReason, BehaveTree},
+};
+use bevy::prelude::*;
+use dyn_clone::DynClone;
+
+/// A task run by a `Behave::action` node, stored inline in the tree and ticked with world access.
+///
+/// Unlike `Behave::spawn` tasks, actions don't spawn an entity, so they suit short, cheap tasks
+/// run by many trees at once. Each time the node runs, the action is cloned from the tree
+/// definition, so fields can hold per-run state.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_behave::prelude::*;
+/// # #[derive(Component)]
+/// # struct Health(f32);
+/// #[derive(Clone)]
+/// struct Heal { per_tick: f32, ticks_left: u32 }
+///
+/// impl BehaveAction for Heal {
+///     fn on_tick(&mut self, ctx: &BehaveCtx, world: &mut World) -> BehaveActionStatus {
+///         let Some(mut health) = world.get_mut::<Health>(ctx.target_entity()) else {
+///             return BehaveActionStatus::Failure(Some("no health".into()));
+///         };
+///         health.0 += self.per_tick;
+///         self.ticks_left -= 1;
+///         if self.ticks_left == 0 {
+///             BehaveActionStatus::Success
+///         } else {
+///             BehaveActionStatus::Running
+///         }
+///     }
+/// }
+///
+/// let tree = behave! { Behave::action(Heal { per_tick: 5.0, ticks_left: 3 }) };
+/// ```
+pub trait BehaveAction: DynClone + Send + Sync + 'static {
+    /// Called when the node starts running, just before the first `on_tick`.
+    fn on_start(&mut self, _ctx: &BehaveCtx, _world: &mut World) {}
+    /// Called each time the tree ticks while the action is running, at most once per frame.
+    fn on_tick(&mut self, ctx: &BehaveCtx, world: &mut World) -> BehaveActionStatus;
+    /// Called if the action is stopped before finishing, eg when its subtree is reset, or the
+    /// tree is aborted or removed.
+    fn on_halt(&mut self, _ctx: &BehaveCtx, _world: &mut World) {}
+    /// The name shown when displaying the tree. Defaults to the type name.
+    fn name(&self) -> String {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name).to_string()
+    }
+}
+
+dyn_clone::clone_trait_object!(BehaveAction);
+
+/// Returned from [`BehaveAction::on_tick`].
+#[derive(Clone, Debug)]
+pub enum BehaveActionStatus {
+    /// Still running, tick again next frame.
+    Running,
+    /// The action succeeded.
+    Success,
+    /// The action failed, optionally with a reason that ends up in the tree's `BehaveFailure`
+    /// if this failure makes the tree fail, like `BehaveCtx::failure_because`.
+    Failure(Option<BehaveFailureReason>),
+}
+
+/// A boxed [`BehaveAction`], as stored in the tree definition.
+#[derive(Clone)]
+pub struct DynamicAction(Box<dyn BehaveAction>);
+
+impl DynamicAction {
+    pub(crate) fn new(action: impl BehaveAction) -> Self {
+        Self(Box::new(action))
+    }
+    /// A fresh copy of the action from the tree definition, for a new run of the node.
+    pub(crate) fn instance(&self) -> Box<dyn BehaveAction> {
+        self.0.clone()
+    }
+    /// The name of the action, see [`BehaveAction::name`].
+    pub fn name(&self) -> String {
+        self.0.name()
+    }
+}
+
+impl std::fmt::Debug for DynamicAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DynamicAction({})", self.name())
+    }
+}
+
+/// Runtime state of an action node.
+#[derive(Clone)]
+pub(crate) enum ActionTaskStatus {
+    NotStarted,
+    Running {
+        /// None while a command has taken the action to tick it.
+        action: Option<Box<dyn BehaveAction>>,
+        ctx: BehaveCtx,
+        /// The `BehaveClock` tick it was last ticked, so it's ticked at most once per frame.
+        ticked: u64,
+    },
+    Complete(bool),
+}
+
+impl std::fmt::Debug for ActionTaskStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionTaskStatus::NotStarted => write!(f, "NotStarted"),
+            ActionTaskStatus::Running { ticked, .. } => write!(f, "Running(ticked: {ticked})"),
+            ActionTaskStatus::Complete(success) => write!(f, "Complete({success})"),
+        }
+    }
+}
+
+/// Ticks an action taken from its node, then puts it back (or records its result) if the node
+/// is still waiting on this run. Otherwise the node was halted while the action was out, so we
+/// halt the action here.
+pub(crate) fn tick_action(
+    world: &mut World,
+    mut action: Box<dyn BehaveAction>,
+    ctx: BehaveCtx,
+    start: bool,
+) {
+    if start {
+        action.on_start(&ctx, world);
+    }
+    let result = action.on_tick(&ctx, world);
+    if let Some(mut bt) = world.get_mut::<BehaveTree>(ctx.behave_entity())
+        && bt.is_current(&ctx)
+        && let Some(mut node) = bt.tree.get_mut(ctx.task_node())
+        && let BehaveNode::Action {
+            task_status, run, ..
+        } = node.value()
+        && let ActionTaskStatus::Running { action: slot, .. } = task_status
+    {
+        match result {
+            BehaveActionStatus::Running => *slot = Some(action),
+            BehaveActionStatus::Success => *task_status = ActionTaskStatus::Complete(true),
+            BehaveActionStatus::Failure(reason) => {
+                *task_status = ActionTaskStatus::Complete(false);
+                run.failure = reason;
+            }
+        }
+        return;
+    }
+    if matches!(result, BehaveActionStatus::Running) {
+        action.on_halt(&ctx, world);
+    }
+}
@@ -0,0 +1,77 @@
+//! Task nodes that run a future on bevy's `AsyncComputeTaskPool`.
+use crate::prelude::*;
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, futures::check_ready};
+use std::{future::Future, pin::Pin, sync::Arc};
+
+type AsyncTaskFn = dyn Fn(BehaveCtx) -> Pin<Box<dyn Future<Output = bool> + Send>> + Send + Sync;
+
+/// Spawned on the task entity of a `Behave::async_task` node. Starts the future when first
+/// polled, and reports its result when it resolves.
+///
+/// Dropping the component (eg when the task entity is despawned because the node was halted)
+/// drops the `Task`, cancelling the future.
+#[derive(Component)]
+pub(crate) struct BehaveAsyncTask {
+    make_future: Arc<AsyncTaskFn>,
+    state: AsyncTaskState,
+}
+
+enum AsyncTaskState {
+    NotStarted,
+    Running(Task<bool>),
+    /// Reported, waiting for the task entity to be despawned.
+    Done,
+}
+
+/// Clones start from scratch, so each run of the node starts its own future.
+impl Clone for BehaveAsyncTask {
+    fn clone(&self) -> Self {
+        Self {
+            make_future: self.make_future.clone(),
+            state: AsyncTaskState::NotStarted,
+        }
+    }
+}
+
+impl BehaveAsyncTask {
+    pub(crate) fn new<F, Fut>(f: F) -> Self
+    where
+        F: Fn(BehaveCtx) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = bool> + Send + 'static,
+    {
+        Self {
+            make_future: Arc::new(move |ctx| Box::pin(f(ctx))),
+            state: AsyncTaskState::NotStarted,
+        }
+    }
+}
+
+pub(crate) fn poll_async_tasks(
+    mut q: Query<(&mut BehaveAsyncTask, &BehaveCtx)>,
+    q_paused: Query<(), With<BehavePaused>>,
+    mut commands: Commands,
+) {
+    for (mut async_task, ctx) in q.iter_mut() {
+        if q_paused.contains(ctx.behave_entity()) {
+            continue;
+        }
+        let BehaveAsyncTask { make_future, state } = &mut *async_task;
+        if matches!(state, AsyncTaskState::NotStarted) {
+            let pool = AsyncComputeTaskPool::get_or_init(Default::default);
+            *state = AsyncTaskState::Running(pool.spawn(make_future(*ctx)));
+        }
+        let AsyncTaskState::Running(task) = state else {
+            continue;
+        };
+        if let Some(success) = check_ready(task) {
+            *state = AsyncTaskState::Done;
+            let report = if success {
+                ctx.success()
+            } else {
+                ctx.failure()
+            };
+            commands.trigger(report);
+        }
+    }
+}
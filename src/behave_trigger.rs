@@ -1,15 +1,51 @@
 // This is a a trigger version of an updated version of https://crates.io/crates/bevy_dynamic_bundle
-use crate::ctx::BehaveCtx;
+use crate::{
+    ctx::BehaveCtx,
+    plugin::{BehaveSettings, stop_tree_in_world, trigger_event_target},
+    prelude::BehaveTree,
+};
 use bevy::prelude::*;
 use dyn_clone::DynClone;
+use std::any::TypeId;
 
 /// A wrapper around a user-provided type, which we trigger to test a condition or cause an action.
-#[derive(Event, Debug, Clone)]
+///
+/// Targets the tree's target entity, or the tree entity with [`BehaveTriggerTarget::Tree`], so
+/// as well as global observers, observers added to that entity with `.observe()` will run.
+#[derive(EntityEvent, Debug, Clone)]
 pub struct BehaveTrigger<T: Clone + Send + Sync> {
+    #[event_target]
+    pub(crate) target: Entity,
     pub(crate) inner: T,
     pub(crate) ctx: BehaveCtx,
 }
 
+/// Which entity [`BehaveTrigger`] events target. Optional, on the entity holding the `BehaveTree`.
+///
+/// Observers added to the targeted entity only run for triggers from trees that target it, eg:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_behave::prelude::*;
+/// # #[derive(Clone)]
+/// # struct Bark;
+/// # fn setup(mut commands: Commands, tree: Tree<Behave>) {
+/// commands
+///     .spawn(Name::new("Dog"))
+///     .observe(|trigger: On<BehaveTrigger<Bark>>, mut commands: Commands| {
+///         commands.trigger(trigger.ctx().success());
+///     })
+///     .with_child(BehaveTree::new(tree));
+/// # }
+/// ```
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BehaveTriggerTarget {
+    /// The tree's target entity, see `BehaveTargetEntity`.
+    #[default]
+    Target,
+    /// The entity holding the `BehaveTree`.
+    Tree,
+}
+
 impl<T: Clone + Send + Sync> BehaveTrigger<T> {
     /// Returns the context for this trigger event.
     pub fn ctx(&self) -> &BehaveCtx {
@@ -22,10 +58,49 @@ impl<T: Clone + Send + Sync> BehaveTrigger<T> {
     }
 }
 
+/// Counts the global observers of `BehaveTrigger<T>`, and those added to `target`, or to any
+/// entity if `target` is None.
+fn observer_count<T: Clone + Send + Sync + 'static>(
+    world: &World,
+    target: Option<Entity>,
+) -> usize {
+    let Some(observers) = world
+        .event_key::<BehaveTrigger<T>>()
+        .and_then(|key| world.observers().try_get_observers(key))
+    else {
+        return 0;
+    };
+    let entity_observers = observers.entity_observers();
+    let entity_count = match target {
+        Some(target) => entity_observers.get(&target).map_or(0, |o| o.len()),
+        None => entity_observers.values().map(|o| o.len()).sum(),
+    };
+    observers.global_observers().len() + entity_count
+}
+
+/// Runs a closure instead of emitting a `BehaveTrigger`, reporting its result straight away.
+fn closure_trigger<F>(f: F) -> impl DynTriggerCommand
+where
+    F: Fn(BehaveCtx, &mut World) -> bool + Clone + Send + Sync + 'static,
+{
+    move |ctx: BehaveCtx, world: &mut World| {
+        let report = if f(ctx, world) {
+            ctx.success()
+        } else {
+            ctx.failure()
+        };
+        world.trigger(report);
+    }
+}
+
 fn world_trigger<T: Clone + Send + Sync + 'static>(bundle: T) -> impl DynTriggerCommand {
     move |ctx: BehaveCtx, world: &mut World| {
-        let ev = BehaveTrigger::<T> { inner: bundle, ctx };
-        world.trigger(ev);
+        let target = trigger_event_target(world, &ctx);
+        world.trigger(BehaveTrigger::<T> {
+            target,
+            inner: bundle,
+            ctx,
+        });
     }
 }
 
@@ -49,6 +124,21 @@ struct CommandWrapper {
 
 impl Command for CommandWrapper {
     fn apply(self, world: &mut World) {
+        let check = world
+            .get_resource::<BehaveSettings>()
+            .is_some_and(|s| s.check_trigger_observers);
+        if check
+            && !self
+                .cmd
+                .is_observed(world, Some(trigger_event_target(world, &self.ctx)))
+        {
+            // interrupt checks trigger with a temporary entity, rather than a tree.
+            if let Some(bt) = world.get::<BehaveTree>(self.ctx.behave_entity()) {
+                let error = bt.unobserved_trigger_error(self.ctx.task_node(), &self.cmd);
+                stop_tree_in_world(world, self.ctx.behave_entity(), error);
+                return;
+            }
+        }
         self.cmd.trig_fn.apply(self.ctx, world);
     }
 }
@@ -59,6 +149,13 @@ pub struct DynamicTrigger {
     #[allow(dead_code)]
     trig_fn: Box<dyn DynTriggerCommand>,
     type_name: String,
+    /// Counts the observers of the `BehaveTrigger<T>`, see `BehavePlugin::with_trigger_checks`.
+    /// None for closures, which don't need observers.
+    observer_count: Option<fn(&World, Option<Entity>) -> usize>,
+    /// The `TypeId` of `T` for `BehaveTrigger<T>`s. None for closures and systems.
+    type_id: Option<TypeId>,
+    /// Displayed instead of `Trigger(T)` for closures and systems.
+    label: Option<String>,
 }
 impl std::fmt::Debug for DynamicTrigger {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -75,6 +172,62 @@ impl DynamicTrigger {
             trig_fn: Box::new(world_trigger(trig)),
             // preserve the type name for debugging
             type_name: std::any::type_name::<T>().to_string(),
+            observer_count: Some(observer_count::<T>),
+            type_id: Some(TypeId::of::<T>()),
+            label: None,
+        }
+    }
+    pub(crate) fn from_fn<F>(label: &'static str, f: F) -> DynamicTrigger
+    where
+        F: Fn(BehaveCtx, &mut World) -> bool + Clone + Send + Sync + 'static,
+    {
+        DynamicTrigger {
+            trig_fn: Box::new(closure_trigger(f)),
+            type_name: std::any::type_name::<F>().to_string(),
+            observer_count: None,
+            type_id: None,
+            label: Some(label.to_string()),
+        }
+    }
+    /// Runs `system` with bevy's system cache, so it's registered once per world and system type.
+    pub(crate) fn from_system<M, S>(system: S) -> DynamicTrigger
+    where
+        S: IntoSystem<In<BehaveCtx>, bool, M> + Copy + Send + Sync + 'static,
+    {
+        let type_name = std::any::type_name::<S>().to_string();
+        DynamicTrigger {
+            trig_fn: Box::new(closure_trigger(move |ctx, world: &mut World| {
+                world
+                    .run_system_cached_with(system, ctx)
+                    .unwrap_or_else(|e| {
+                        warn!("Failed to run conditional system for {ctx}, reporting failure: {e}");
+                        false
+                    })
+            })),
+            label: Some(format!("Conditional({type_name})")),
+            type_name,
+            observer_count: None,
+            type_id: None,
+        }
+    }
+    /// The `TypeId` of `T` for `BehaveTrigger<T>`s, None for closures and systems.
+    pub(crate) fn trigger_type_id(&self) -> Option<TypeId> {
+        self.type_id
+    }
+    /// Are there any global observers for this trigger, or observers on `target` (any entity if
+    /// None)? Always true for closures.
+    pub(crate) fn is_observed(&self, world: &World, target: Option<Entity>) -> bool {
+        self.observer_count
+            .is_none_or(|count| count(world, target) > 0)
+    }
+}
+
+/// `Trigger(T)`, or `Run`/`Condition`/`Conditional(system)` for closures and systems.
+impl std::fmt::Display for DynamicTrigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.label {
+            Some(label) => f.write_str(label),
+            None => write!(f, "Trigger({})", self.type_name),
         }
     }
 }
@@ -113,7 +266,7 @@ mod tests {
     fn send_trigger(mut commands: Commands) {
         let dyn_trig = DynamicTrigger::new(MyStruct(123));
         let tick_ctx = crate::TickCtx::new(Entity::PLACEHOLDER, Entity::PLACEHOLDER, 0.0);
-        let ctx = BehaveCtx::new_for_entity(get_node_id(), &tick_ctx, Entity::PLACEHOLDER);
+        let ctx = BehaveCtx::new_for_entity(get_node_id(), 1, &tick_ctx, Entity::PLACEHOLDER);
         commands.dyn_trigger(dyn_trig, ctx);
     }
 
@@ -0,0 +1,93 @@
+//! Validates and renders bevy_behave tree definition files, without launching the game.
+//!
+//! ```text
+//! bevy_behave [--format text|dot|mermaid] [--lint] FILE.ron...
+//! ```
+//!
+//! Each file contains a RON `BehaveDef`. Named leaves don't need to exist in the game to be
+//! checked, they are rendered as placeholder `Spawn(name)` nodes.
+//! With `--lint`, warnings from `BehaveTree::lint` are also printed, but don't cause a failure.
+//! Exits with status 1 if any file can't be read, parsed, or fails verification.
+use bevy_behave::prelude::*;
+use std::process::ExitCode;
+
+enum Format {
+    Text,
+    Dot,
+    Mermaid,
+}
+
+const USAGE: &str = "usage: bevy_behave [--format text|dot|mermaid] [--lint] FILE.ron...";
+
+fn main() -> ExitCode {
+    let mut format = Format::Text;
+    let mut lint = false;
+    let mut files = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" | "-f" => {
+                format = match args.next().as_deref() {
+                    Some("text") => Format::Text,
+                    Some("dot") => Format::Dot,
+                    Some("mermaid") => Format::Mermaid,
+                    _ => {
+                        eprintln!("{USAGE}");
+                        return ExitCode::FAILURE;
+                    }
+                }
+            }
+            "--lint" => lint = true,
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ => files.push(arg),
+        }
+    }
+    if files.is_empty() {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    }
+
+    let mut ok = true;
+    for file in files {
+        match check_file(&file) {
+            Ok(tree) => {
+                if lint {
+                    for warning in BehaveTree::lint(&tree) {
+                        eprintln!("{file}: warning: {warning}");
+                    }
+                }
+                match format {
+                    Format::Text => print!("{tree}"),
+                    Format::Dot => print!("{}", tree.to_dot()),
+                    Format::Mermaid => print!("{}", tree.to_mermaid()),
+                }
+            }
+            Err(errors) => {
+                ok = false;
+                for error in errors {
+                    eprintln!("{file}: {error}");
+                }
+            }
+        }
+    }
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn check_file(file: &str) -> Result<Tree<Behave>, Vec<String>> {
+    let text = std::fs::read_to_string(file).map_err(|e| vec![e.to_string()])?;
+    let def = ron::from_str::<BehaveDef>(&text).map_err(|e| vec![e.to_string()])?;
+    let tree = def.to_placeholder_tree();
+    let errors = BehaveTree::verify_errors(&tree);
+    if errors.is_empty() {
+        Ok(tree)
+    } else {
+        Err(errors.iter().map(ToString::to_string).collect())
+    }
+}
@@ -0,0 +1,145 @@
+//! Commands to abort, restart, pause and resume a running tree from gameplay code.
+use crate::{
+    BehaveNode, EntityTaskStatus, RunningTask, halt_task, plugin::BehaveAwaitingTrigger, prelude::*,
+};
+use bevy::prelude::*;
+
+/// Inserted on a [`BehaveTree`] entity by `behave_pause`, and removed by `behave_resume`.
+/// Paused trees aren't ticked, and their `Wait` timers and [`BehaveTimeout`]s don't count down.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct BehavePaused {
+    /// `BehaveClock::elapsed_secs` when the tree was paused.
+    since: f32,
+}
+
+/// Extension methods on `EntityCommands` for controlling the [`BehaveTree`] on that entity.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_behave::prelude::*;
+/// fn stun(mut commands: Commands, tree_entity: Entity) {
+///     commands.entity(tree_entity).behave_pause();
+/// }
+/// ```
+pub trait BehaveCommandsExt {
+    /// Stops the tree, halting any running tasks, inserts `BehaveFinished(false)` and triggers
+    /// [`BehaveTreeFinished`]. Aborted trees are kept, whatever their [`BehaveOnFinish`].
+    fn behave_abort(&mut self) -> &mut Self;
+    /// Halts any running tasks, and runs the tree again from the start.
+    /// Works on finished, aborted and paused trees too.
+    fn behave_restart(&mut self) -> &mut Self;
+    /// Stops ticking the tree until `behave_resume`, without halting running tasks.
+    /// No time passes for `Wait` nodes or [`BehaveTimeout`]s while paused.
+    fn behave_pause(&mut self) -> &mut Self;
+    /// Resumes ticking a tree paused with `behave_pause`.
+    fn behave_resume(&mut self) -> &mut Self;
+}
+
+impl BehaveCommandsExt for EntityCommands<'_> {
+    fn behave_abort(&mut self) -> &mut Self {
+        self.queue(abort)
+    }
+    fn behave_restart(&mut self) -> &mut Self {
+        self.queue(restart)
+    }
+    fn behave_pause(&mut self) -> &mut Self {
+        self.queue(pause)
+    }
+    fn behave_resume(&mut self) -> &mut Self {
+        self.queue(resume)
+    }
+}
+
+fn abort(mut entity: EntityWorldMut) {
+    if entity.contains::<BehaveFinished>() {
+        return;
+    }
+    let Some(mut bt) = entity.get_mut::<BehaveTree>() else {
+        warn!("behave_abort on {} which has no BehaveTree", entity.id());
+        return;
+    };
+    let tasks = bt.stop();
+    let bt_entity = entity.id();
+    entity
+        .remove::<(BehaveAwaitingTrigger, BehavePaused)>()
+        .insert(BehaveFinished(false));
+    halt_tasks(entity, tasks).trigger(BehaveTreeFinished {
+        entity: bt_entity,
+        success: false,
+        failure: None,
+    });
+}
+
+fn restart(mut entity: EntityWorldMut) {
+    let Some(mut bt) = entity.get_mut::<BehaveTree>() else {
+        warn!("behave_restart on {} which has no BehaveTree", entity.id());
+        return;
+    };
+    let tasks = bt.take_running_tasks();
+    bt.reset();
+    entity.remove::<(
+        BehaveFinished,
+        BehaveFailure,
+        BehaveError,
+        BehaveAwaitingTrigger,
+        BehavePaused,
+    )>();
+    halt_tasks(entity, tasks);
+}
+
+fn pause(mut entity: EntityWorldMut) {
+    if !entity.contains::<BehaveTree>() || entity.contains::<BehavePaused>() {
+        return;
+    }
+    let since = entity.world().resource::<BehaveClock>().elapsed_secs();
+    entity.insert(BehavePaused { since });
+}
+
+fn resume(mut entity: EntityWorldMut) {
+    let Some(BehavePaused { since }) = entity.take::<BehavePaused>() else {
+        return;
+    };
+    let paused_secs = entity.world().resource::<BehaveClock>().elapsed_secs() - since;
+    let Some(mut bt) = entity.get_mut::<BehaveTree>() else {
+        return;
+    };
+    // shift the start of every timer forward, as if no time passed while paused
+    let mut task_entities = Vec::new();
+    for node in bt.tree.values_mut() {
+        match node {
+            BehaveNode::Wait {
+                start_time: Some(start_time),
+                ..
+            } => *start_time += paused_secs,
+            BehaveNode::DynamicEntity {
+                task_status: EntityTaskStatus::Started(task_entity),
+                run,
+                ..
+            } => {
+                run.started_secs += paused_secs;
+                task_entities.push(*task_entity);
+            }
+            BehaveNode::TriggerReq { run, .. } | BehaveNode::Action { run, .. } => {
+                run.started_secs += paused_secs
+            }
+            _ => {}
+        }
+    }
+    entity.world_scope(|world| {
+        for task_entity in task_entities {
+            if let Some(mut timeout) = world.get_mut::<BehaveTimeout>(task_entity) {
+                timeout.start_time += paused_secs;
+            }
+        }
+    });
+}
+
+fn halt_tasks(entity: EntityWorldMut<'_>, tasks: Vec<RunningTask>) -> &mut World {
+    let world = entity.into_world_mut();
+    let mut commands = world.commands();
+    for task in tasks {
+        halt_task(&mut commands, task);
+    }
+    world.flush();
+    world
+}
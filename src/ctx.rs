@@ -1,14 +1,16 @@
-use crate::prelude::*;
+use crate::{plugin::BehaveSettings, prelude::*};
 use bevy::prelude::*;
 use ego_tree::NodeId;
 
 pub(crate) fn plugin(app: &mut App) {
     app.add_observer(on_behave_status_report);
+    app.add_observer(on_behave_tree_removed);
+    app.add_observer(on_task_ctx_removed);
 }
 
 /// Provided to the user's bevy system or observer fn, so they have a way to report status
 /// back to the tree, and to look up the target entity etc.
-#[derive(Component, Debug, Copy, Clone)]
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
 pub struct BehaveCtx {
     /// the entity holding the behaviour tree
     bt_entity: Entity,
@@ -25,15 +27,23 @@ pub struct BehaveCtx {
     ctx_type: CtxType,
     /// the time when the behaviour was spawned/triggered
     elapsed_secs: f32,
+    /// which run of the task node this is, so reports from earlier runs can be ignored.
+    generation: u32,
+    /// the task node's label, from `Behave::named`.
+    label: Option<&'static str>,
 }
 
 impl std::fmt::Display for BehaveCtx {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "BehaveCtx(bt: {}, target: {}, type: {:?})",
+            "BehaveCtx(bt: {}, target: {}, type: {:?}",
             self.bt_entity, self.target_entity, self.ctx_type
-        )
+        )?;
+        if let Some(label) = self.label {
+            write!(f, ", label: {label}")?;
+        }
+        write!(f, ")")
     }
 }
 
@@ -43,41 +53,60 @@ impl std::fmt::Display for BehaveCtx {
 enum CtxType {
     Trigger,
     Entity,
+    Action,
 }
 
 impl BehaveCtx {
-    pub(crate) fn new_for_trigger(task_node: NodeId, tick_ctx: &TickCtx) -> Self {
-        Self::new(task_node, tick_ctx, CtxType::Trigger, None)
+    pub(crate) fn new_for_trigger(task_node: NodeId, generation: u32, tick_ctx: &TickCtx) -> Self {
+        Self::new(task_node, generation, tick_ctx, CtxType::Trigger, None)
+    }
+    pub(crate) fn new_for_action(task_node: NodeId, generation: u32, tick_ctx: &TickCtx) -> Self {
+        Self::new(task_node, generation, tick_ctx, CtxType::Action, None)
     }
     pub(crate) fn new_for_entity(
         task_node: NodeId,
+        generation: u32,
         tick_ctx: &TickCtx,
         task_entity: Entity,
     ) -> Self {
-        Self::new(task_node, tick_ctx, CtxType::Entity, Some(task_entity))
+        Self::new(
+            task_node,
+            generation,
+            tick_ctx,
+            CtxType::Entity,
+            Some(task_entity),
+        )
     }
     fn new(
         task_node: NodeId,
+        generation: u32,
         tick_ctx: &TickCtx,
         ctx_type: CtxType,
         task_entity: Option<Entity>,
     ) -> Self {
         Self {
             task_node,
+            generation,
             task_entity,
             bt_entity: tick_ctx.bt_entity,
             target_entity: tick_ctx.target_entity,
             sup_entity: tick_ctx.supervisor_entity,
             elapsed_secs: tick_ctx.elapsed_secs,
             ctx_type,
+            label: tick_ctx.labels.get(&task_node).copied(),
         }
     }
-    /// The `Time::elapsed_secs()`` when this behaviour was spawned/triggered.
+    /// The [`BehaveClock::elapsed_secs`] when this behaviour was spawned/triggered.
+    /// This follows `Time::elapsed_secs()`, except in rollback mode where the clock is simulated.
     /// Useful to calculate how long the behaviour has been running:
-    /// `time.elapsed_secs() - ctx.elapsed_secs_epoch()`
+    /// `clock.elapsed_secs() - ctx.elapsed_secs()`
     pub fn elapsed_secs(&self) -> f32 {
         self.elapsed_secs
     }
+    /// The task node's label, if it was given one with `Behave::named`.
+    pub fn label(&self) -> Option<&'static str> {
+        self.label
+    }
     /// Was this context created for a trigger_req node?
     pub fn is_for_trigger(&self) -> bool {
         self.ctx_type == CtxType::Trigger
@@ -86,6 +115,10 @@ impl BehaveCtx {
     pub fn is_for_entity(&self) -> bool {
         self.ctx_type == CtxType::Entity
     }
+    /// Was this context created for an action node?
+    pub fn is_for_action(&self) -> bool {
+        self.ctx_type == CtxType::Action
+    }
     /// Returns the event that reports success for this context.
     pub fn success(&self) -> BehaveStatusReport {
         BehaveStatusReport::Success(*self)
@@ -94,6 +127,13 @@ impl BehaveCtx {
     pub fn failure(&self) -> BehaveStatusReport {
         BehaveStatusReport::Failure(*self)
     }
+    /// Returns the event that reports failure for this context, with a reason that ends up in
+    /// the tree's [`BehaveFailure`] if this failure makes the tree fail.
+    ///
+    /// `ctx.failure_because("target out of range")`
+    pub fn failure_because(&self, reason: impl Into<BehaveFailureReason>) -> BehaveStatusReport {
+        BehaveStatusReport::FailureBecause(*self, reason.into())
+    }
     /// Returns the target entity for this context.
     /// The target entity is typically the character or game object the behaviour tree is controlling.
     /// See also: [`BehaveTargetEntity`]
@@ -115,6 +155,11 @@ impl BehaveCtx {
     pub fn supervisor_entity(&self) -> Option<Entity> {
         self.sup_entity
     }
+    /// Which run of this task node the context is for. Each time a looping tree runs the node
+    /// again, this increases, and status reports from earlier runs are ignored.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
     /// Returns the node id of the task that this context is for.
     /// Used internally by the tree to report status.
     pub(crate) fn task_node(&self) -> NodeId {
@@ -129,6 +174,8 @@ pub enum BehaveStatusReport {
     Success(BehaveCtx),
     /// Reports failure for a task
     Failure(BehaveCtx),
+    /// Reports failure for a task, with a reason, see [`BehaveCtx::failure_because`]
+    FailureBecause(BehaveCtx, BehaveFailureReason),
 }
 
 impl BehaveStatusReport {
@@ -137,10 +184,22 @@ impl BehaveStatusReport {
         match self {
             BehaveStatusReport::Success(ctx) => ctx,
             BehaveStatusReport::Failure(ctx) => ctx,
+            BehaveStatusReport::FailureBecause(ctx, _) => ctx,
         }
     }
 }
 
+/// Triggered on a task entity that is still running when the tree stops needing it, just before
+/// the entity is despawned. Happens when the task's part of the tree is reset to run again,
+/// or when the tree is removed or stops with a [`BehaveError`].
+///
+/// Observe this to clean up after a task, eg to stop the character moving.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct BehaveHalted {
+    /// The task entity being halted.
+    pub entity: Entity,
+}
+
 // when we recieve a status report, we add the result to the tree node, so it's processed the
 // next time the tree ticks.
 fn on_behave_status_report(
@@ -155,13 +214,28 @@ fn on_behave_status_report(
         debug!("Failed to get bt entity during status report {:?}", trigger);
         return;
     };
+    if !bt.is_current(ctx) {
+        // a late report from an earlier run of this node, eg after a loop or restart reset it.
+        if bt.logging {
+            info!("Ignoring stale status report for a node that isn't waiting for one: {ctx}");
+        } else {
+            debug!("Ignoring stale status report for a node that isn't waiting for one: {ctx}");
+        }
+        if let Some(task_entity) = ctx.task_entity() {
+            commands.entity(task_entity).try_despawn();
+        }
+        return;
+    }
     // remove the waiting trigger component, so the tree will be ticked next time.
     commands
         .entity(ctx.bt_entity)
         .remove::<BehaveAwaitingTrigger>();
     let task_entity = match trigger.event() {
-        BehaveStatusReport::Success(ctx) => bt.set_node_result(ctx, true),
-        BehaveStatusReport::Failure(ctx) => bt.set_node_result(ctx, false),
+        BehaveStatusReport::Success(ctx) => bt.set_node_result(ctx, Ok(())),
+        BehaveStatusReport::Failure(ctx) => bt.set_node_result(ctx, Err(None)),
+        BehaveStatusReport::FailureBecause(ctx, reason) => {
+            bt.set_node_result(ctx, Err(Some(reason.clone())))
+        }
     };
     // despawn the entity used for this task now that it is complete.
     // if this was a TriggerReq task, there won't be a task entity.
@@ -169,3 +243,47 @@ fn on_behave_status_report(
         commands.entity(task_entity).try_despawn();
     }
 }
+
+// halt any running tasks when the tree goes away, so they get a chance to clean up.
+fn on_behave_tree_removed(
+    trigger: On<Remove, BehaveTree>,
+    mut q_bt: Query<&mut BehaveTree>,
+    mut commands: Commands,
+) {
+    if let Ok(mut bt) = q_bt.get_mut(trigger.event().entity) {
+        bt.halt_running_tasks(&mut commands);
+    }
+}
+
+// a task entity that goes away without reporting would leave its tree waiting forever,
+// so report a result on its behalf.
+fn on_task_ctx_removed(
+    trigger: On<Remove, BehaveCtx>,
+    q_ctx: Query<&BehaveCtx>,
+    q_bt: Query<&BehaveTree, Without<BehaveFinished>>,
+    settings: Res<BehaveSettings>,
+    mut commands: Commands,
+) {
+    let task_entity = trigger.event().entity;
+    let Ok(ctx) = q_ctx.get(task_entity) else {
+        return;
+    };
+    let Ok(bt) = q_bt.get(ctx.behave_entity()) else {
+        return;
+    };
+    if !bt.is_current(ctx) || !bt.is_running_task(ctx.task_node(), task_entity) {
+        return;
+    }
+    let success = settings.despawned_task_success;
+    warn!(
+        "Task entity {task_entity} for node {} in tree {} was removed without reporting a result, reporting {}",
+        bt.node_name(ctx.task_node()),
+        ctx.behave_entity(),
+        if success { "success" } else { "failure" },
+    );
+    commands.trigger(if success {
+        ctx.success()
+    } else {
+        ctx.failure_because("task entity removed without reporting a result")
+    });
+}
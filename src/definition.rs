@@ -0,0 +1,104 @@
+//! A serialisable tree definition, for tree files edited by designers and checked by tools.
+//!
+//! Enabled with the `serde` feature.
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A serialisable description of a tree, made of the built-in nodes and named leaves.
+///
+/// Control flow nodes list their children, even the ones that only permit one, so that a
+/// file with the wrong number of children can still be loaded and then reported by
+/// [`BehaveTree::verify`]. In RON, this looks like:
+///
+/// ```ron
+/// Forever([
+///     Sequence([
+///         Leaf("WaitUntilPlayerIsNear"),
+///         Invert([ Leaf("IsTired") ]),
+///         Wait(5.0),
+///     ]),
+/// ])
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum BehaveDef {
+    /// `Behave::Sequence`
+    Sequence(Vec<BehaveDef>),
+    /// `Behave::Fallback`
+    Fallback(Vec<BehaveDef>),
+    /// `Behave::Invert`
+    Invert(Vec<BehaveDef>),
+    /// `Behave::Forever`
+    Forever(Vec<BehaveDef>),
+    /// `Behave::While`
+    While(Vec<BehaveDef>),
+    /// `Behave::IfThen`
+    IfThen(Vec<BehaveDef>),
+    /// `Behave::AlwaysSucceed`
+    AlwaysSucceed,
+    /// `Behave::AlwaysFail`
+    AlwaysFail,
+    /// `Behave::Wait`
+    Wait(f32),
+    /// A task or condition, looked up by name when converting to a `Tree<Behave>`.
+    Leaf(String),
+}
+
+impl BehaveDef {
+    /// Converts to a `Tree<Behave>`, using `leaf` to look up the behaviour for each named leaf.
+    ///
+    /// Returns the name of the first leaf that `leaf` doesn't know about, as the error.
+    pub fn to_tree(
+        &self,
+        mut leaf: impl FnMut(&str) -> Option<Behave>,
+    ) -> Result<Tree<Behave>, String> {
+        let mut tree = Tree::new(self.to_behave(&mut leaf)?);
+        self.append_children(&mut tree.root_mut(), &mut leaf)?;
+        Ok(tree)
+    }
+
+    /// Converts to a `Tree<Behave>` where every named leaf is a `Behave::spawn_named` with
+    /// no components. Useful for verifying and rendering a tree outside of the game.
+    pub fn to_placeholder_tree(&self) -> Tree<Behave> {
+        self.to_tree(|name| Some(Behave::spawn_named(name.to_string(), ())))
+            .expect("placeholder leaves are always found")
+    }
+
+    fn to_behave(&self, leaf: &mut impl FnMut(&str) -> Option<Behave>) -> Result<Behave, String> {
+        Ok(match self {
+            BehaveDef::Sequence(_) => Behave::Sequence,
+            BehaveDef::Fallback(_) => Behave::Fallback,
+            BehaveDef::Invert(_) => Behave::Invert,
+            BehaveDef::Forever(_) => Behave::Forever,
+            BehaveDef::While(_) => Behave::While,
+            BehaveDef::IfThen(_) => Behave::IfThen,
+            BehaveDef::AlwaysSucceed => Behave::AlwaysSucceed,
+            BehaveDef::AlwaysFail => Behave::AlwaysFail,
+            BehaveDef::Wait(secs) => Behave::Wait(*secs),
+            BehaveDef::Leaf(name) => leaf(name).ok_or_else(|| name.clone())?,
+        })
+    }
+
+    fn children(&self) -> &[BehaveDef] {
+        match self {
+            BehaveDef::Sequence(children)
+            | BehaveDef::Fallback(children)
+            | BehaveDef::Invert(children)
+            | BehaveDef::Forever(children)
+            | BehaveDef::While(children)
+            | BehaveDef::IfThen(children) => children,
+            _ => &[],
+        }
+    }
+
+    fn append_children(
+        &self,
+        node: &mut NodeMut<Behave>,
+        leaf: &mut impl FnMut(&str) -> Option<Behave>,
+    ) -> Result<(), String> {
+        for child in self.children() {
+            let mut child_node = node.append(child.to_behave(leaf)?);
+            child.append_children(&mut child_node, leaf)?;
+        }
+        Ok(())
+    }
+}
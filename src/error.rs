@@ -0,0 +1,150 @@
+//! Structured errors for invalid trees.
+use crate::{BehaveNode, NodeLabels, prelude::*};
+use bevy::prelude::Component;
+use std::ops::RangeInclusive;
+
+/// Why a tree definition was rejected by [`BehaveTree::try_new`], or a tree stopped with a
+/// [`BehaveError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BehaveTreeError {
+    /// A node has the wrong number of children for its type.
+    InvalidChildren {
+        /// Path to the node from the root, eg `root/Sequence[2]/While`.
+        path: String,
+        /// The node's `Display`.
+        node: String,
+        /// How many children the node has.
+        children: usize,
+        /// How many children this type of node permits.
+        permitted: RangeInclusive<usize>,
+    },
+    /// A `Behave::trigger` node whose `BehaveTrigger<T>` has no observers, so it would wait
+    /// forever. Only checked when enabled with `BehavePlugin::with_trigger_checks`.
+    UnobservedTrigger {
+        /// Path to the node from the root.
+        path: String,
+        /// The trigger type, from `std::any::type_name`.
+        trigger: String,
+    },
+}
+
+impl std::fmt::Display for BehaveTreeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BehaveTreeError::InvalidChildren {
+                path,
+                node,
+                children,
+                permitted,
+            } => {
+                write!(f, "{path}: {node} has {children} children, permitted: ")?;
+                fmt_range(permitted, f)
+            }
+            BehaveTreeError::UnobservedTrigger { path, trigger } => {
+                write!(f, "{path}: no observers for BehaveTrigger<{trigger}>")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BehaveTreeError {}
+
+/// Fatal problem found while ticking a tree, which stops the tree running.
+///
+/// Inserted alongside `BehaveFinished(false)`, eg when a tree built with the ego_tree api
+/// without calling [`BehaveTree::verify`] has a node with the wrong number of children,
+/// or a trigger has no observers (when `BehavePlugin::with_trigger_checks` is enabled).
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct BehaveError(pub BehaveTreeError);
+
+impl std::fmt::Display for BehaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// The error for a node found to have the wrong number of children while ticking.
+pub(crate) fn invalid_node_error(
+    tree: &Tree<BehaveNode>,
+    labels: &NodeLabels,
+    id: NodeId,
+) -> BehaveTreeError {
+    let node = tree.get(id).expect("node id from this tree");
+    BehaveTreeError::InvalidChildren {
+        path: node_path(node, labels),
+        node: node.value().definition_name(),
+        children: node.children().count(),
+        permitted: node.value().permitted_children(),
+    }
+}
+
+/// The path to a node in a running tree, matching the paths from [`BehaveTree::verify_errors`].
+pub(crate) fn node_path(node: NodeRef<BehaveNode>, labels: &NodeLabels) -> String {
+    let mut path = String::new();
+    for n in node
+        .ancestors()
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .chain([node])
+    {
+        path = match n.parent() {
+            None => "root".to_string(),
+            Some(parent) => child_path(
+                &path,
+                labels
+                    .get(&n.id())
+                    .map_or_else(|| n.value().definition_name(), |label| label.to_string()),
+                n.prev_siblings().count(),
+                parent.children().count(),
+            ),
+        };
+    }
+    path
+}
+
+fn fmt_range(range: &RangeInclusive<usize>, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match (*range.start(), *range.end()) {
+        (start, usize::MAX) => write!(f, "{start} or more"),
+        (start, end) if start == end => write!(f, "exactly {start}"),
+        (start, end) => write!(f, "{start} to {end}"),
+    }
+}
+
+/// Appends a child's segment to its parent's path, eg `root/Sequence[2]`.
+/// The index is only included when the child has siblings.
+pub(crate) fn child_path(
+    parent: &str,
+    label: impl std::fmt::Display,
+    index: usize,
+    siblings: usize,
+) -> String {
+    if siblings > 1 {
+        format!("{parent}/{label}[{index}]")
+    } else {
+        format!("{parent}/{label}")
+    }
+}
+
+/// Collects an error for each node with the wrong number of children, in depth-first order.
+pub(crate) fn tree_errors(node: NodeRef<Behave>, path: String, errors: &mut Vec<BehaveTreeError>) {
+    let n = node.value();
+    let children = node.children().count();
+    let permitted = n.permitted_children();
+    if !permitted.contains(&children) {
+        errors.push(BehaveTreeError::InvalidChildren {
+            path: path.clone(),
+            node: n.to_string(),
+            children,
+            permitted,
+        });
+    }
+    for (i, child) in node.children().enumerate() {
+        tree_errors(
+            child,
+            child_path(&path, child.value().path_name(), i, children),
+            errors,
+        );
+    }
+}
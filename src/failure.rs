@@ -0,0 +1,125 @@
+//! Why a tree failed: reasons reported by tasks, and the node the failure came from.
+use crate::{BehaveNodeStatus, error::node_path, prelude::*};
+use bevy::prelude::Component;
+use std::{any::Any, borrow::Cow, sync::Arc};
+
+/// Why a task failed, reported with [`BehaveCtx::failure_because`].
+///
+/// Has a message, and optionally a typed payload for code that wants to react to the failure,
+/// eg a planner deciding what to try next.
+/// ```
+/// # use bevy_behave::prelude::*;
+/// #[derive(Debug, PartialEq)]
+/// struct PathBlocked { distance: f32 }
+///
+/// let reason = BehaveFailureReason::new("path blocked").with_payload(PathBlocked { distance: 3.0 });
+/// assert_eq!(reason.to_string(), "path blocked");
+/// assert_eq!(reason.payload::<PathBlocked>(), Some(&PathBlocked { distance: 3.0 }));
+/// ```
+#[derive(Clone)]
+pub struct BehaveFailureReason {
+    message: Cow<'static, str>,
+    payload: Option<Arc<dyn Any + Send + Sync>>,
+}
+
+impl BehaveFailureReason {
+    /// A reason with just a message.
+    pub fn new(message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            message: message.into(),
+            payload: None,
+        }
+    }
+    /// Attaches a typed payload, retrieved with [`BehaveFailureReason::payload`].
+    pub fn with_payload<T: Any + Send + Sync>(mut self, payload: T) -> Self {
+        self.payload = Some(Arc::new(payload));
+        self
+    }
+    /// The message describing the failure.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+    /// The payload, if one of type `T` was attached.
+    pub fn payload<T: Any>(&self) -> Option<&T> {
+        self.payload.as_ref()?.downcast_ref()
+    }
+}
+
+impl std::fmt::Debug for BehaveFailureReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BehaveFailureReason")
+            .field("message", &self.message)
+            .field("has_payload", &self.payload.is_some())
+            .finish()
+    }
+}
+
+impl std::fmt::Display for BehaveFailureReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<&'static str> for BehaveFailureReason {
+    fn from(message: &'static str) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<String> for BehaveFailureReason {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+/// Where a failed tree's failure came from.
+///
+/// Inserted alongside `BehaveFinished(false)`, and included in the [`BehaveTreeFinished`] event.
+/// The failure is traced from the root down through the failed children of each control flow
+/// node, to the node that first failed, eg a task that reported failure, or an `Invert` whose
+/// child succeeded.
+#[derive(Component, Debug, Clone)]
+pub struct BehaveFailure {
+    /// The node the failure came from.
+    pub node: NodeId,
+    /// The node's `Display`, eg `Trigger(MoveTo)`.
+    pub node_name: String,
+    /// Path to the node from the root, eg `root/Sequence[2]/Trigger(MoveTo)`.
+    pub path: String,
+    /// The reason the task gave, if it failed with [`BehaveCtx::failure_because`].
+    pub reason: Option<BehaveFailureReason>,
+}
+
+impl std::fmt::Display for BehaveFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} failed", self.path, self.node_name)?;
+        if let Some(reason) = &self.reason {
+            write!(f, ": {reason}")?;
+        }
+        Ok(())
+    }
+}
+
+impl BehaveTree {
+    /// Where the tree's failure came from, if the tree has failed.
+    pub fn failure(&self) -> Option<BehaveFailure> {
+        let mut node = self.tree.root();
+        if *node.value().status() != Some(BehaveNodeStatus::Failure) {
+            return None;
+        }
+        // the most recently ticked failed child is the last one, eg the final child of a Fallback
+        while let Some(child) = node
+            .children()
+            .rev()
+            .find(|c| *c.value().status() == Some(BehaveNodeStatus::Failure))
+        {
+            node = child;
+        }
+        Some(BehaveFailure {
+            node: node.id(),
+            node_name: self.node_name(node.id()),
+            path: node_path(node, &self.labels),
+            reason: node.value().task_run().and_then(|run| run.failure.clone()),
+        })
+    }
+}
@@ -0,0 +1,101 @@
+//! Read-only view of a running tree's nodes, for debug overlays and tests.
+use crate::{BehaveNode, BehaveNodeStatus, error::node_path, prelude::*};
+
+/// The status of a node in a running tree, see [`BehaveNodeInfo::status`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BehaveStatus {
+    /// Running, eg waiting on a task, a timer, or a running child.
+    Running,
+    /// Finished successfully.
+    Success,
+    /// Finished with a failure.
+    Failure,
+    /// Finished, and will be reset to run again on the next tick, eg the children of a loop.
+    PendingReset,
+}
+
+impl From<BehaveNodeStatus> for BehaveStatus {
+    fn from(status: BehaveNodeStatus) -> Self {
+        match status {
+            BehaveNodeStatus::Success => BehaveStatus::Success,
+            BehaveNodeStatus::Failure => BehaveStatus::Failure,
+            BehaveNodeStatus::Running
+            | BehaveNodeStatus::RunningTimer
+            | BehaveNodeStatus::AwaitingTrigger => BehaveStatus::Running,
+            BehaveNodeStatus::PendingReset => BehaveStatus::PendingReset,
+        }
+    }
+}
+
+/// A node in a running tree, from [`BehaveTree::nodes`], [`BehaveTree::node`] or
+/// [`BehaveTree::active_path`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BehaveNodeInfo {
+    /// The node's id in this tree.
+    pub id: NodeId,
+    /// The node type and its parameters, eg `Sequence`, `Wait(1s)` or `Trigger(MoveTo)`.
+    pub kind: String,
+    /// The node's label, from `Behave::named`.
+    pub label: Option<&'static str>,
+    /// Path to the node from the root, eg `root/Sequence[2]/Trigger(MoveTo)`.
+    pub path: String,
+    /// How many ancestors the node has, 0 for the root.
+    pub depth: usize,
+    /// None if the node hasn't run yet, or has been reset to run again.
+    pub status: Option<BehaveStatus>,
+}
+
+impl BehaveTree {
+    /// Every node in the tree with its current status, in depth-first order.
+    /// ```
+    /// # use bevy_behave::prelude::*;
+    /// let bt = BehaveTree::new(behave! {
+    ///     Behave::Sequence => {
+    ///         Behave::Wait(1.0),
+    ///         Behave::Wait(2.0),
+    ///     }
+    /// });
+    /// let kinds: Vec<_> = bt.nodes().map(|n| n.kind).collect();
+    /// assert_eq!(kinds, ["Sequence", "Wait(1s)", "Wait(2s)"]);
+    /// assert!(bt.nodes().all(|n| n.status.is_none()));
+    /// ```
+    pub fn nodes(&self) -> impl Iterator<Item = BehaveNodeInfo> + '_ {
+        self.tree
+            .root()
+            .descendants()
+            .map(|node| self.node_info(node))
+    }
+
+    /// The node with this id, if it's in the tree.
+    pub fn node(&self, node_id: NodeId) -> Option<BehaveNodeInfo> {
+        self.tree.get(node_id).map(|node| self.node_info(node))
+    }
+
+    /// The running nodes, from the root down to the running task. Empty when the tree isn't
+    /// running, eg before its first tick or once it has finished.
+    pub fn active_path(&self) -> Vec<BehaveNodeInfo> {
+        let running = |node: &NodeRef<BehaveNode>| {
+            node.value()
+                .status()
+                .is_some_and(|s| BehaveStatus::from(s) == BehaveStatus::Running)
+        };
+        let mut path = Vec::new();
+        let mut next = Some(self.tree.root()).filter(running);
+        while let Some(node) = next {
+            path.push(self.node_info(node));
+            next = node.children().find(running);
+        }
+        path
+    }
+
+    fn node_info(&self, node: NodeRef<BehaveNode>) -> BehaveNodeInfo {
+        BehaveNodeInfo {
+            id: node.id(),
+            kind: node.value().definition_name(),
+            label: self.label(node.id()),
+            path: node_path(node, &self.labels),
+            depth: node.ancestors().count(),
+            status: node.value().status().map(BehaveStatus::from),
+        }
+    }
+}
@@ -6,30 +6,79 @@ use std::{borrow::Cow, ops::RangeInclusive};
 use bevy::prelude::*;
 use ego_tree::*;
 
+mod action;
+mod async_task;
 mod behave_trigger;
+mod control;
 mod ctx;
+#[cfg(feature = "serde")]
+mod definition;
 mod dyn_bundle;
+mod error;
+mod failure;
+mod inspect;
+mod lint;
+mod node_events;
 mod plugin;
+mod registry;
+mod render;
+mod rollback;
+mod task;
+mod watchdog;
+#[cfg(feature = "xml")]
+mod xml;
 
 #[cfg(test)]
 mod tests;
 
+use action::{ActionTaskStatus, BehaveAction, DynamicAction, tick_action};
+use async_task::BehaveAsyncTask;
 use behave_trigger::*;
 use ctx::*;
 use dyn_bundle::prelude::*;
+use failure::BehaveFailureReason;
+use node_events::tick_with_node_events;
 
 // in case users want to construct the tree without using the macro, we reexport:
 pub use ego_tree;
+
+// so the derive macros' `::bevy_behave` paths resolve inside this crate too, eg in tests
+#[cfg(feature = "derive")]
+extern crate self as bevy_behave;
+
+/// Used by the code generated by the derive macros, not public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use bevy::ecs::system::SystemParamItem;
+}
 use plugin::TickCtx;
 
 /// Includes the ego_tree `tree!` macro for easy tree construction.
 /// this crate also re-exports `ego_tree` so you can construct trees manually.
 pub mod prelude {
+    pub use super::action::{BehaveAction, BehaveActionStatus};
     pub use super::behave;
-    pub use super::behave_trigger::BehaveTrigger;
+    pub use super::behave_trigger::{BehaveTrigger, BehaveTriggerTarget};
+    pub use super::control::{BehaveCommandsExt, BehavePaused};
     pub use super::ctx::*;
+    #[cfg(feature = "serde")]
+    pub use super::definition::BehaveDef;
+    pub use super::error::{BehaveError, BehaveTreeError};
+    pub use super::failure::{BehaveFailure, BehaveFailureReason};
+    pub use super::inspect::{BehaveNodeInfo, BehaveStatus};
+    pub use super::lint::{BehaveLint, BehaveLintKind};
+    pub use super::node_events::{BehaveNodeEvents, BehaveNodeFinished, BehaveNodeStarted};
     pub use super::plugin::*;
+    pub use super::registry::{BehaveTriggerInfo, BehaveTriggerRegistry};
+    pub use super::render::BehaveRender;
+    pub use super::rollback::{BehaveClock, BehaveTreeSnapshot};
+    pub use super::task::{BehaveAppExt, BehaveCondition, BehaveTask};
+    pub use super::watchdog::BehaveWatchdog;
+    #[cfg(feature = "xml")]
+    pub use super::xml::{BehaveXmlError, BehaveXmlImporter, BehaveXmlPorts};
     pub use super::{Behave, BehaveFinished};
+    #[cfg(feature = "derive")]
+    pub use bevy_behave_derive::{BehaveCondition, BehaveTask};
     pub use ego_tree::*;
 }
 
@@ -60,7 +109,7 @@ pub struct BehaveFinished(pub bool);
 ///
 /// Constuction is via static fns on Behave, so we can do the dynamic bundle stuff.
 /// although this probably makes it hard to load the tree def from an asset file?
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum Behave {
     /// Waits this many seconds before Succeeding
     Wait(f32),
@@ -85,6 +134,9 @@ pub enum Behave {
     /// Returns a result from a trigger. Can be used as a conditional (returning success or failure)
     /// or simply to execute some bevy systems code without spawning an entity.
     TriggerReq(DynamicTrigger),
+    /// Runs a [`BehaveAction`] inline, without spawning an entity.
+    /// Use the `Behave::action` fn to create.
+    Action(DynamicAction),
     /// Loops forever
     Forever,
     /// Runs second child as long as first child succeeds, in a loop.
@@ -92,8 +144,18 @@ pub enum Behave {
     /// If the first child succeeds, run the second child.
     /// (otherwise, run the third child, if present)
     IfThen,
+    /// Any other node, with a label to identify it by. Use the `Behave::named` fn to create.
+    Labelled {
+        /// The label, see [`BehaveTree::find`](crate::prelude::BehaveTree::find).
+        label: &'static str,
+        /// The labelled node.
+        behave: Box<Behave>,
+    },
 }
 
+/// Labels of the nodes in a tree.
+pub(crate) type NodeLabels = std::collections::HashMap<NodeId, &'static str>;
+
 impl std::fmt::Display for Behave {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -105,9 +167,11 @@ impl std::fmt::Display for Behave {
             Behave::Invert => write!(f, "Invert"),
             Behave::AlwaysSucceed => write!(f, "AlwaysSucceed"),
             Behave::AlwaysFail => write!(f, "AlwaysFail"),
-            Behave::TriggerReq(t) => write!(f, "Trigger({})", t.type_name()),
+            Behave::TriggerReq(t) => write!(f, "{t}"),
+            Behave::Action(a) => write!(f, "Action({})", a.name()),
             Behave::Forever => write!(f, "Forever"),
             Behave::IfThen => write!(f, "IfThen"),
+            Behave::Labelled { label, behave } => write!(f, "{behave} \"{label}\""),
         }
     }
 }
@@ -140,18 +204,156 @@ impl Behave {
     pub fn trigger<T: Clone + Send + Sync + 'static>(value: T) -> Self {
         Behave::TriggerReq(DynamicTrigger::new(value))
     }
+    /// Creates a task node that runs the closure when reached, succeeding if it returns true.
+    /// Saves defining a trigger type and an observer for small actions:
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_behave::prelude::*;
+    /// # #[derive(Resource)]
+    /// # struct Score(u32);
+    /// let tree = behave! {
+    ///     Behave::run(|_ctx, world| {
+    ///         world.resource_mut::<Score>().0 += 1;
+    ///         true
+    ///     })
+    /// };
+    /// ```
+    pub fn run<F>(f: F) -> Self
+    where
+        F: Fn(BehaveCtx, &mut World) -> bool + Clone + Send + Sync + 'static,
+    {
+        Behave::TriggerReq(DynamicTrigger::from_fn("Run", f))
+    }
+    /// Creates a task node that checks the closure when reached, succeeding if it returns true.
+    /// Like [`Behave::run`], but the closure only gets read access to the world.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_behave::prelude::*;
+    /// # #[derive(Component)]
+    /// # struct Health(f32);
+    /// let tree = behave! {
+    ///     Behave::condition(|ctx, world| {
+    ///         world.get::<Health>(ctx.target_entity()).is_some_and(|h| h.0 < 20.0)
+    ///     })
+    /// };
+    /// ```
+    pub fn condition<F>(f: F) -> Self
+    where
+        F: Fn(BehaveCtx, &World) -> bool + Clone + Send + Sync + 'static,
+    {
+        Behave::TriggerReq(DynamicTrigger::from_fn(
+            "Condition",
+            move |ctx, world: &mut World| f(ctx, world),
+        ))
+    }
+    /// Creates a task node that runs a condition system when reached, succeeding if it returns
+    /// true. The system gets the node's [`BehaveCtx`] as input, and can use any system params:
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_behave::prelude::*;
+    /// # #[derive(Component)]
+    /// # struct Health(f32);
+    /// fn is_hurt(In(ctx): In<BehaveCtx>, q: Query<&Health>) -> bool {
+    ///     q.get(ctx.target_entity()).is_ok_and(|h| h.0 < 20.0)
+    /// }
+    /// let tree = behave! { Behave::conditional(is_hurt) };
+    /// ```
+    /// The system must be a function or a non-capturing closure, so it can be run with
+    /// `World::run_system_cached_with`, which registers it once per world. It's run with exclusive
+    /// world access from a command, so it completes before the tree next ticks.
+    pub fn conditional<M>(
+        system: impl IntoSystem<In<BehaveCtx>, bool, M> + Copy + Send + Sync + 'static,
+    ) -> Self {
+        Behave::TriggerReq(DynamicTrigger::from_system(system))
+    }
+    /// Creates a task node that runs the future returned by `f` on bevy's `AsyncComputeTaskPool`,
+    /// succeeding if it resolves to true. Suits long running work such as pathfinding, which
+    /// would otherwise need a task component polling a `Task` handle:
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_behave::prelude::*;
+    /// let tree = behave! {
+    ///     Behave::async_task(|ctx| async move {
+    ///         // find a path for ctx.target_entity() ...
+    ///         true
+    ///     })
+    /// };
+    /// ```
+    /// The future is started on a task entity, like `Behave::spawn`, and polled each frame. If the
+    /// node is halted before the future resolves, the task entity is despawned and the future is
+    /// cancelled. Futures of paused trees keep running, but their result isn't reported until
+    /// the tree resumes.
+    pub fn async_task<F, Fut>(f: F) -> Self
+    where
+        F: Fn(BehaveCtx) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = bool> + Send + 'static,
+    {
+        Behave::spawn_named("AsyncTask", BehaveAsyncTask::new(f))
+    }
+    /// Creates a new Behave::Action, which runs the [`BehaveAction`] inline when this node runs,
+    /// ticking it with world access until it succeeds or fails. Cheaper than `Behave::spawn`,
+    /// since no task entity is spawned.
+    pub fn action(action: impl BehaveAction) -> Self {
+        Behave::Action(DynamicAction::new(action))
+    }
+    /// Labels this node, so it can be found with [`BehaveTree::find`](crate::prelude::BehaveTree::find). The label is used in place
+    /// of the node type in paths, eg `root/combat/Trigger(Attack)`, and is available to tasks with
+    /// [`BehaveCtx::label`]. Labelling an already labelled node replaces its label.
+    /// ```
+    /// # use bevy_behave::prelude::*;
+    /// let tree = behave! {
+    ///     Behave::Fallback => {
+    ///         Behave::Sequence.named("combat") => {
+    ///             Behave::Wait(1.0).named("wind up"),
+    ///         },
+    ///         Behave::Wait(5.0),
+    ///     }
+    /// };
+    /// let bt = BehaveTree::new(tree);
+    /// assert!(bt.find("wind up").is_some());
+    /// ```
+    pub fn named(self, label: &'static str) -> Behave {
+        let behave = match self {
+            Behave::Labelled { behave, .. } => behave,
+            behave => Box::new(behave),
+        };
+        Behave::Labelled { label, behave }
+    }
+    /// The node's label, if it has one.
+    pub fn label(&self) -> Option<&'static str> {
+        match self {
+            Behave::Labelled { label, .. } => Some(label),
+            _ => None,
+        }
+    }
+    /// The node without its label.
+    pub(crate) fn unlabelled(&self) -> &Behave {
+        match self {
+            Behave::Labelled { behave, .. } => behave.unlabelled(),
+            behave => behave,
+        }
+    }
+    /// The node's segment in a path: its label, or its type.
+    pub(crate) fn path_name(&self) -> String {
+        match self.label() {
+            Some(label) => label.to_string(),
+            None => self.to_string(),
+        }
+    }
     /// The permitted number of children for this node
-    pub(crate) fn permitted_children(&self) -> RangeInclusive<usize> {
+    pub fn permitted_children(&self) -> RangeInclusive<usize> {
         match self {
+            Behave::Labelled { behave, .. } => behave.permitted_children(),
             Behave::Sequence => 0..=usize::MAX,
             Behave::Fallback => 0..=usize::MAX,
-            Behave::Forever => 1..=usize::MAX,
+            Behave::Forever => 1..=1,
             Behave::While => 1..=2,
             Behave::IfThen => 2..=3,
             Behave::Invert => 1..=1,
             // Task nodes have no children:
             Behave::Wait(_) => 0..=0,
             Behave::TriggerReq(_) => 0..=0,
+            Behave::Action(_) => 0..=0,
             Behave::DynamicEntity { .. } => 0..=0,
             // AlwaysSucceed and AlwaysFail are pseudo task nodes that don't have children:
             Behave::AlwaysSucceed => 0..=0,
@@ -178,6 +380,7 @@ pub(crate) enum BehaveNode {
         status: Option<BehaveNodeStatus>,
         bundle: DynamicBundel,
         name: Cow<'static, str>,
+        run: TaskRun,
     },
     SequenceFlow {
         status: Option<BehaveNodeStatus>,
@@ -198,6 +401,13 @@ pub(crate) enum BehaveNode {
         status: Option<BehaveNodeStatus>,
         task_status: TriggerTaskStatus,
         trigger: DynamicTrigger,
+        run: TaskRun,
+    },
+    Action {
+        status: Option<BehaveNodeStatus>,
+        task_status: ActionTaskStatus,
+        action: DynamicAction,
+        run: TaskRun,
     },
     While {
         status: Option<BehaveNodeStatus>,
@@ -207,6 +417,28 @@ pub(crate) enum BehaveNode {
     },
 }
 
+/// Identifies the current run of a task node, which starts each time the task is spawned or
+/// triggered.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TaskRun {
+    /// Incremented each run, to spot stale status reports.
+    pub(crate) generation: u32,
+    /// `BehaveClock::elapsed_secs` when the run started.
+    pub(crate) started_secs: f32,
+    /// The reason given if this run reported failure with `BehaveCtx::failure_because`.
+    pub(crate) failure: Option<BehaveFailureReason>,
+}
+
+impl TaskRun {
+    fn next(&self, tick_ctx: &TickCtx) -> Self {
+        Self {
+            generation: self.generation.wrapping_add(1),
+            started_secs: tick_ctx.elapsed_secs,
+            failure: None,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 enum EntityTaskStatus {
     NotStarted,
@@ -226,6 +458,7 @@ impl BehaveNode {
         match self {
             BehaveNode::Forever { status } => status,
             BehaveNode::TriggerReq { status, .. } => status,
+            BehaveNode::Action { status, .. } => status,
             BehaveNode::Wait { status, .. } => status,
             BehaveNode::DynamicEntity { status, .. } => status,
             BehaveNode::SequenceFlow { status } => status,
@@ -241,6 +474,7 @@ impl BehaveNode {
         match self {
             BehaveNode::Forever { status } => status,
             BehaveNode::TriggerReq { status, .. } => status,
+            BehaveNode::Action { status, .. } => status,
             BehaveNode::Wait { status, .. } => status,
             BehaveNode::DynamicEntity { status, .. } => status,
             BehaveNode::SequenceFlow { status } => status,
@@ -255,21 +489,8 @@ impl BehaveNode {
 }
 
 impl std::fmt::Display for BehaveNode {
-    #[rustfmt::skip]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            BehaveNode::Forever { .. } => write!(f, "Forever")?,
-            BehaveNode::TriggerReq { trigger, .. } => write!(f, "TriggerReq({})", trigger.type_name())?,
-            BehaveNode::Wait { secs_to_wait, .. } => write!(f, "Wait({secs_to_wait})")?,
-            BehaveNode::DynamicEntity {name, .. } => write!(f, "DynamicEntity({name})")?,
-            BehaveNode::SequenceFlow { .. } => write!(f, "SequenceFlow")?,
-            BehaveNode::FallbackFlow { .. } => write!(f, "FallbackFlow")?,
-            BehaveNode::Invert { .. } => write!(f, "Invert")?,
-            BehaveNode::AlwaysSucceed { .. } => write!(f, "AlwaysSucceed")?,
-            BehaveNode::AlwaysFail { .. } => write!(f, "AlwaysFail")?,
-            BehaveNode::While { .. } => write!(f, "While")?,
-            BehaveNode::IfThen { .. } => write!(f, "IfThen")?,
-        }
+        self.fmt_kind(f)?;
         match self.status() {
             Some(BehaveNodeStatus::Success) => write!(f, " --> ✅"),
             Some(BehaveNodeStatus::Failure) => write!(f, " --> ❌"),
@@ -283,6 +504,109 @@ impl std::fmt::Display for BehaveNode {
 }
 
 impl BehaveNode {
+    /// Writes the node type and parameters, without the status.
+    #[rustfmt::skip]
+    pub(crate) fn fmt_kind(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BehaveNode::Forever { .. } => write!(f, "Forever"),
+            BehaveNode::TriggerReq { trigger, .. } => write!(f, "TriggerReq({})", trigger.type_name()),
+            BehaveNode::Action { action, .. } => write!(f, "Action({})", action.name()),
+            BehaveNode::Wait { secs_to_wait, .. } => write!(f, "Wait({secs_to_wait})"),
+            BehaveNode::DynamicEntity {name, .. } => write!(f, "DynamicEntity({name})"),
+            BehaveNode::SequenceFlow { .. } => write!(f, "SequenceFlow"),
+            BehaveNode::FallbackFlow { .. } => write!(f, "FallbackFlow"),
+            BehaveNode::Invert { .. } => write!(f, "Invert"),
+            BehaveNode::AlwaysSucceed { .. } => write!(f, "AlwaysSucceed"),
+            BehaveNode::AlwaysFail { .. } => write!(f, "AlwaysFail"),
+            BehaveNode::While { .. } => write!(f, "While"),
+            BehaveNode::IfThen { .. } => write!(f, "IfThen"),
+        }
+    }
+
+    /// The `Display` of the `Behave` this node was created from, for error messages and paths.
+    pub(crate) fn definition_name(&self) -> String {
+        match self {
+            BehaveNode::Forever { .. } => "Forever".to_string(),
+            BehaveNode::TriggerReq { trigger, .. } => trigger.to_string(),
+            BehaveNode::Action { action, .. } => format!("Action({})", action.name()),
+            BehaveNode::Wait { secs_to_wait, .. } => format!("Wait({secs_to_wait}s)"),
+            BehaveNode::DynamicEntity { name, .. } => format!("Spawn({name})"),
+            BehaveNode::SequenceFlow { .. } => "Sequence".to_string(),
+            BehaveNode::FallbackFlow { .. } => "Fallback".to_string(),
+            BehaveNode::Invert { .. } => "Invert".to_string(),
+            BehaveNode::AlwaysSucceed { .. } => "AlwaysSucceed".to_string(),
+            BehaveNode::AlwaysFail { .. } => "AlwaysFail".to_string(),
+            BehaveNode::While { .. } => "While".to_string(),
+            BehaveNode::IfThen { .. } => "IfThen".to_string(),
+        }
+    }
+
+    /// The number of children this node can tick, matching `Behave::permitted_children`.
+    pub(crate) fn permitted_children(&self) -> RangeInclusive<usize> {
+        match self {
+            BehaveNode::SequenceFlow { .. } | BehaveNode::FallbackFlow { .. } => 0..=usize::MAX,
+            BehaveNode::Forever { .. } | BehaveNode::Invert { .. } => 1..=1,
+            BehaveNode::While { .. } => 1..=2,
+            BehaveNode::IfThen { .. } => 2..=3,
+            BehaveNode::Wait { .. }
+            | BehaveNode::TriggerReq { .. }
+            | BehaveNode::Action { .. }
+            | BehaveNode::DynamicEntity { .. }
+            | BehaveNode::AlwaysSucceed { .. }
+            | BehaveNode::AlwaysFail { .. } => 0..=0,
+        }
+    }
+}
+
+impl BehaveNode {
+    /// The current run of a task node, its generation is stamped on its [`BehaveCtx`].
+    pub(crate) fn task_run(&self) -> Option<&TaskRun> {
+        match self {
+            BehaveNode::DynamicEntity { run, .. }
+            | BehaveNode::TriggerReq { run, .. }
+            | BehaveNode::Action { run, .. } => Some(run),
+            _ => None,
+        }
+    }
+    /// Is this task node running, and waiting for a result? False before it starts, once it has a
+    /// result, and after a reset.
+    pub(crate) fn awaiting_result(&self) -> bool {
+        matches!(
+            self,
+            BehaveNode::DynamicEntity {
+                task_status: EntityTaskStatus::Started(_),
+                ..
+            } | BehaveNode::TriggerReq {
+                task_status: TriggerTaskStatus::Triggered,
+                ..
+            } | BehaveNode::Action {
+                task_status: ActionTaskStatus::Running { .. },
+                ..
+            }
+        )
+    }
+    /// Stops tracking a running task, returning it so it can be halted with [`halt_task`].
+    pub(crate) fn halt(&mut self) -> Option<RunningTask> {
+        match self {
+            BehaveNode::DynamicEntity { task_status, .. } => {
+                let EntityTaskStatus::Started(task_entity) = *task_status else {
+                    return None;
+                };
+                *task_status = EntityTaskStatus::NotStarted;
+                Some(RunningTask::Entity(task_entity))
+            }
+            BehaveNode::Action { task_status, .. } => {
+                let ActionTaskStatus::Running { action, ctx, .. } =
+                    std::mem::replace(task_status, ActionTaskStatus::NotStarted)
+                else {
+                    return None;
+                };
+                // if a command has the action to tick it, the command halts it instead
+                action.map(|action| RunningTask::Action(action, ctx))
+            }
+            _ => None,
+        }
+    }
     pub(crate) fn reset(&mut self) {
         match self {
             BehaveNode::Forever { status } => {
@@ -310,6 +634,14 @@ impl BehaveNode {
                 *status = None;
                 *task_status = EntityTaskStatus::NotStarted;
             }
+            BehaveNode::Action {
+                status,
+                task_status,
+                ..
+            } => {
+                *status = None;
+                *task_status = ActionTaskStatus::NotStarted;
+            }
             BehaveNode::SequenceFlow { status } => {
                 *status = None;
             }
@@ -335,11 +667,19 @@ impl BehaveNode {
     }
     pub(crate) fn new(behave: Behave) -> Self {
         match behave {
+            Behave::Labelled { behave, .. } => Self::new(*behave),
             Behave::Forever => Self::Forever { status: None },
             Behave::TriggerReq(trig_fn) => Self::TriggerReq {
                 status: None,
                 task_status: TriggerTaskStatus::NotTriggered,
                 trigger: trig_fn,
+                run: TaskRun::default(),
+            },
+            Behave::Action(action) => Self::Action {
+                status: None,
+                task_status: ActionTaskStatus::NotStarted,
+                action,
+                run: TaskRun::default(),
             },
             Behave::Wait(secs_to_wait) => Self::Wait {
                 start_time: None,
@@ -354,6 +694,7 @@ impl BehaveNode {
                 status: None,
                 bundle,
                 name,
+                run: TaskRun::default(),
             },
             Behave::While => Self::While { status: None },
             Behave::Sequence => Self::SequenceFlow { status: None },
@@ -366,53 +707,103 @@ impl BehaveNode {
     }
 }
 
+/// Triggers [`BehaveHalted`] on a running task entity so it can clean up, then despawns it.
+/// Running actions get their `on_halt` called instead.
+pub(crate) fn halt_task(commands: &mut Commands, task: RunningTask) {
+    match task {
+        RunningTask::Entity(task_entity) => {
+            commands.trigger(BehaveHalted {
+                entity: task_entity,
+            });
+            commands.entity(task_entity).try_despawn();
+        }
+        RunningTask::Action(mut action, ctx) => {
+            commands.queue(move |world: &mut World| action.on_halt(&ctx, world));
+        }
+    }
+}
+
+/// A task that was still running when its node stopped tracking it, see [`BehaveNode::halt`].
+pub(crate) enum RunningTask {
+    /// A task entity, from `Behave::spawn`.
+    Entity(Entity),
+    /// A `Behave::action`.
+    Action(Box<dyn BehaveAction>, BehaveCtx),
+}
+
 // sucks there aren't good traversal fns on NodeMut like there are on NodeRef..
-fn reset_descendants(n: &mut NodeMut<BehaveNode>) {
+fn reset_descendants(n: &mut NodeMut<BehaveNode>, commands: &mut Commands) {
     // info!("Restting node: {:?}", n.id());
+    if let Some(task) = n.value().halt() {
+        halt_task(commands, task);
+    }
     n.value().reset();
     if let Some(mut sibling) = n.next_sibling() {
-        reset_descendants(&mut sibling);
+        reset_descendants(&mut sibling, commands);
     }
     if let Some(mut child) = n.first_child() {
-        reset_descendants(&mut child);
+        reset_descendants(&mut child, commands);
     }
 }
 
+/// Ticks a node and its children, returning the node's status.
+/// Errors with the id of a node that doesn't have the children it needs to run.
 fn tick_node(
     n: &mut NodeMut<BehaveNode>,
     commands: &mut Commands,
     tick_ctx: &TickCtx,
-) -> BehaveNodeStatus {
+) -> Result<BehaveNodeStatus, NodeId> {
+    if !tick_ctx.node_events.emits(n.id(), &tick_ctx.labels) {
+        return tick_node_inner(n, commands, tick_ctx);
+    }
+    let (node, before) = (n.id(), *n.value().status());
+    let label = tick_ctx.labels.get(&node).copied();
+    tick_with_node_events(
+        commands,
+        tick_ctx.bt_entity,
+        node,
+        label,
+        before,
+        |commands| tick_node_inner(n, commands, tick_ctx),
+    )
+}
+
+fn tick_node_inner(
+    n: &mut NodeMut<BehaveNode>,
+    commands: &mut Commands,
+    tick_ctx: &TickCtx,
+) -> Result<BehaveNodeStatus, NodeId> {
     use BehaveNode::*;
     // if logging {
     //     info!("tick_node: {:?} = {}", n.id(), n.value());
     // }
     // short circuit nodes that have already got a result
     let reset_needed = match n.value().status() {
-        Some(BehaveNodeStatus::Success) => return BehaveNodeStatus::Success,
-        Some(BehaveNodeStatus::Failure) => return BehaveNodeStatus::Failure,
+        Some(BehaveNodeStatus::Success) => return Ok(BehaveNodeStatus::Success),
+        Some(BehaveNodeStatus::Failure) => return Ok(BehaveNodeStatus::Failure),
         Some(BehaveNodeStatus::PendingReset) => true,
         _ => false,
     };
     if reset_needed {
         *n.value().status_mut() = Some(BehaveNodeStatus::Running);
-        reset_descendants(n);
+        reset_descendants(n, commands);
     }
     let task_node = n.id();
-    match n.value() {
+    let status = match n.value() {
         While { .. } => {
             *n.value().status_mut() = Some(BehaveNodeStatus::Running);
-            let mut first_child = n
-                .first_child()
-                .expect("While node first child must exist (the conditional)");
-            match tick_node(&mut first_child, commands, tick_ctx) {
+            // the first child is the conditional
+            let Some(mut first_child) = n.first_child() else {
+                return Err(task_node);
+            };
+            match tick_node(&mut first_child, commands, tick_ctx)? {
                 BehaveNodeStatus::Success => {
                     *first_child.value().status_mut() = Some(BehaveNodeStatus::Success);
                     // if the conditional succeeds, we run the second child if present.
                     // also supported a while node with just one child, which will simply repeat
                     // until that child fails.
                     if let Some(mut second_child) = first_child.next_sibling() {
-                        match tick_node(&mut second_child, commands, tick_ctx) {
+                        match tick_node(&mut second_child, commands, tick_ctx)? {
                             BehaveNodeStatus::Success => {
                                 *second_child.value().status_mut() =
                                     Some(BehaveNodeStatus::Success);
@@ -443,31 +834,31 @@ fn tick_node(
         }
         IfThen { .. } => {
             *n.value().status_mut() = Some(BehaveNodeStatus::Running);
-            let mut conditional_child = n
-                .first_child()
-                .expect("IfThen node first child must exist (the 'if condition' child)");
+            // the first child is the 'if condition' child
+            let Some(mut conditional_child) = n.first_child() else {
+                return Err(task_node);
+            };
             // evaluate the condition child
-            match tick_node(&mut conditional_child, commands, tick_ctx) {
+            match tick_node(&mut conditional_child, commands, tick_ctx)? {
                 BehaveNodeStatus::Success => {
                     // the condition child succeeded, so the If node returns the result of evaluating the then child.
                     *conditional_child.value().status_mut() = Some(BehaveNodeStatus::Success);
-                    let mut then_child = conditional_child
-                        .next_sibling()
-                        .expect("IfThen node second child must exist (the 'then' child)");
-                    let then_result = tick_node(&mut then_child, commands, tick_ctx);
+                    let Some(mut then_child) = conditional_child.next_sibling() else {
+                        return Err(task_node);
+                    };
+                    let then_result = tick_node(&mut then_child, commands, tick_ctx)?;
                     *n.value().status_mut() = Some(then_result);
                     then_result
                 }
                 BehaveNodeStatus::Failure => {
                     // the condition child failed, an "else" child is optional. run if present:
                     *conditional_child.value().status_mut() = Some(BehaveNodeStatus::Failure);
-                    if let Some(mut else_child) = conditional_child
-                        .next_sibling()
-                        .expect("If nodes must have exactly two or three children")
-                        .next_sibling()
-                    {
+                    let Some(mut then_child) = conditional_child.next_sibling() else {
+                        return Err(task_node);
+                    };
+                    if let Some(mut else_child) = then_child.next_sibling() {
                         // if there is an else child, the If node returns the result of evaluating the else child.
-                        let else_result = tick_node(&mut else_child, commands, tick_ctx);
+                        let else_result = tick_node(&mut else_child, commands, tick_ctx)?;
                         *n.value().status_mut() = Some(else_result);
                         else_result
                     } else {
@@ -485,11 +876,10 @@ fn tick_node(
         }
         Forever { .. } => {
             *n.value().status_mut() = Some(BehaveNodeStatus::Running);
-            let mut only_child = n.first_child().expect("Forever nodes must have a child");
-            if only_child.has_siblings() {
-                panic!("Forever nodes must have a single child, not multiple children");
-            }
-            match tick_node(&mut only_child, commands, tick_ctx) {
+            let Some(mut only_child) = n.first_child().filter(|c| !c.has_siblings()) else {
+                return Err(task_node);
+            };
+            match tick_node(&mut only_child, commands, tick_ctx)? {
                 // if our child node completes, reset next tick so we can run it again
                 BehaveNodeStatus::Success | BehaveNodeStatus::Failure => {
                     *n.value().status_mut() = Some(BehaveNodeStatus::PendingReset);
@@ -503,8 +893,10 @@ fn tick_node(
             task_status: task_status @ TriggerTaskStatus::NotTriggered,
             status,
             trigger,
+            run,
         } => {
-            let ctx = BehaveCtx::new_for_trigger(task_node, tick_ctx);
+            *run = run.next(tick_ctx);
+            let ctx = BehaveCtx::new_for_trigger(task_node, run.generation, tick_ctx);
             commands.dyn_trigger(trigger.clone(), ctx);
             // Don't use AwaitingTrigger for this, because of ordering issues..
             // the trigger response arrives BEFORE we insert the BehaveAwaitingTrigger component,
@@ -530,12 +922,59 @@ fn tick_node(
             *status = Some(BehaveNodeStatus::AwaitingTrigger);
             BehaveNodeStatus::AwaitingTrigger
         }
+        // start the action, it's ticked by a command with world access
+        Action {
+            task_status: task_status @ ActionTaskStatus::NotStarted,
+            status,
+            action,
+            run,
+        } => {
+            *run = run.next(tick_ctx);
+            let ctx = BehaveCtx::new_for_action(task_node, run.generation, tick_ctx);
+            let action = action.instance();
+            *task_status = ActionTaskStatus::Running {
+                action: None,
+                ctx,
+                ticked: tick_ctx.clock_tick,
+            };
+            commands.queue(move |world: &mut World| tick_action(world, action, ctx, true));
+            *status = Some(BehaveNodeStatus::Running);
+            BehaveNodeStatus::Running
+        }
+        // tick the action again, at most once per frame
+        Action {
+            task_status:
+                ActionTaskStatus::Running {
+                    action: slot,
+                    ctx,
+                    ticked,
+                },
+            ..
+        } if slot.is_some() && *ticked != tick_ctx.clock_tick => {
+            let action = slot.take().expect("checked above");
+            let ctx = *ctx;
+            *ticked = tick_ctx.clock_tick;
+            commands.queue(move |world: &mut World| tick_action(world, action, ctx, false));
+            BehaveNodeStatus::Running
+        }
+        // already ticked this frame, so it doesn't need ticking again until the next one
+        #[rustfmt::skip]
+        Action { task_status: ActionTaskStatus::Running { .. }, .. } => BehaveNodeStatus::RunningTimer,
+        #[rustfmt::skip]
+        Action { task_status: ActionTaskStatus::Complete(true), status, .. } => {
+            *status = Some(BehaveNodeStatus::Success);
+            BehaveNodeStatus::Success
+        }
+        #[rustfmt::skip]
+        Action { task_status: ActionTaskStatus::Complete(false), status, .. } => {
+            *status = Some(BehaveNodeStatus::Failure);
+            BehaveNodeStatus::Failure
+        }
         Invert { .. } => {
-            let mut only_child = n.first_child().expect("Invert nodes must have a child");
-            if only_child.has_siblings() {
-                panic!("Invert nodes must have a single child, not multiple children");
-            }
-            let res = match tick_node(&mut only_child, commands, tick_ctx) {
+            let Some(mut only_child) = n.first_child().filter(|c| !c.has_siblings()) else {
+                return Err(task_node);
+            };
+            let res = match tick_node(&mut only_child, commands, tick_ctx)? {
                 BehaveNodeStatus::Success => BehaveNodeStatus::Failure, // swapped
                 BehaveNodeStatus::Failure => BehaveNodeStatus::Success, // swapped
                 BehaveNodeStatus::PendingReset => BehaveNodeStatus::Running,
@@ -576,7 +1015,7 @@ fn tick_node(
             let elapsed = tick_ctx.elapsed_secs - *start_time;
             if elapsed > *secs_to_wait {
                 *status = Some(BehaveNodeStatus::Success);
-                return BehaveNodeStatus::Success;
+                return Ok(BehaveNodeStatus::Success);
             }
             BehaveNodeStatus::RunningTimer
         }
@@ -586,10 +1025,12 @@ fn tick_node(
             status,
             bundle,
             name: _,
+            run,
         } => {
+            *run = run.next(tick_ctx);
             let mut e = commands.spawn(());
             e.insert(ChildOf(tick_ctx.bt_entity));
-            let ctx = BehaveCtx::new_for_entity(task_node, tick_ctx, e.id());
+            let ctx = BehaveCtx::new_for_entity(task_node, run.generation, tick_ctx, e.id());
             // NB: if the component in the dyn bundle has an OnAdd which reports success or failure
             //     immediately, the entity will be despawned instantly, so you can't do something
             //     like .set_parent on it after doing the insertion (we set_parent above).
@@ -611,8 +1052,16 @@ fn tick_node(
             *status = Some(BehaveNodeStatus::AwaitingTrigger);
             BehaveNodeStatus::AwaitingTrigger
         }
-        #[rustfmt::skip]
-        DynamicEntity{ task_status: EntityTaskStatus::Started(_), .. } => unreachable!("Short circuit should prevent this while AwaitingTrigger"),
+        // normally short circuited while AwaitingTrigger, but reachable after restoring a snapshot
+        // or setting the status by hand, so keep waiting for the task entity to report.
+        DynamicEntity {
+            task_status: EntityTaskStatus::Started(_),
+            status,
+            ..
+        } => {
+            *status = Some(BehaveNodeStatus::AwaitingTrigger);
+            BehaveNodeStatus::AwaitingTrigger
+        }
         // this is when a trigger has reported a result, and we need to process it and update status
         #[rustfmt::skip]
         DynamicEntity {task_status: EntityTaskStatus::Complete(true), status, ..} => {
@@ -629,12 +1078,13 @@ fn tick_node(
             // info!("SequenceFlow. Processing children");
             let Some(mut child) = n.first_child() else {
                 warn!("SequenceFlow with no children, returning success anyway");
-                return BehaveNodeStatus::Success;
+                *n.value().status_mut() = Some(BehaveNodeStatus::Success);
+                return Ok(BehaveNodeStatus::Success);
             };
 
             let mut final_status;
             loop {
-                match tick_node(&mut child, commands, tick_ctx) {
+                match tick_node(&mut child, commands, tick_ctx)? {
                     BehaveNodeStatus::Success => {
                         final_status = BehaveNodeStatus::Success;
                         if let Ok(next_child) = child.into_next_sibling() {
@@ -665,12 +1115,13 @@ fn tick_node(
         FallbackFlow { .. } => {
             let Some(mut child) = n.first_child() else {
                 warn!("FallbackFlow with no children, returning success anyway");
-                return BehaveNodeStatus::Success;
+                *n.value().status_mut() = Some(BehaveNodeStatus::Success);
+                return Ok(BehaveNodeStatus::Success);
             };
 
             let mut final_status;
             loop {
-                match tick_node(&mut child, commands, tick_ctx) {
+                match tick_node(&mut child, commands, tick_ctx)? {
                     BehaveNodeStatus::Failure => {
                         // a child fails, try the next one, or if no more children, we failed.
                         final_status = BehaveNodeStatus::Failure;
@@ -698,7 +1149,8 @@ fn tick_node(
             *status = Some(final_status);
             final_status
         }
-    }
+    };
+    Ok(status)
 }
 
 /// Modifed version of ego_tree's tree! macro, to allow merging subtrees:
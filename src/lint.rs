@@ -0,0 +1,199 @@
+//! Opt-in static checks for tree definitions that are valid, but probably not what you meant.
+use crate::{error::child_path, prelude::*};
+
+/// A warning about a valid tree definition, from [`BehaveTree::lint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BehaveLint {
+    /// What's wrong.
+    pub kind: BehaveLintKind,
+    /// Path to the node from the root, eg `root/Sequence[2]/While`.
+    pub path: String,
+    /// The node's `Display`.
+    pub node: String,
+}
+
+/// The kinds of problem [`BehaveTree::lint`] looks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BehaveLintKind {
+    /// This node and its later siblings can never run, because an earlier child of a `Fallback`
+    /// can never fail (eg `AlwaysSucceed`), or an earlier child of a `Sequence` can never succeed
+    /// (eg `AlwaysFail`).
+    UnreachableChildren {
+        /// How many children can never run, starting with this one.
+        count: usize,
+    },
+    /// An `Invert` whose only child is another `Invert`.
+    DoubleInvert,
+    /// A `Sequence` or `Fallback` with no children, which succeeds immediately.
+    EmptyControlFlow,
+    /// A `Forever` with no `Wait`, spawn or trigger leaf beneath it, so it loops without ever
+    /// waiting on anything.
+    ForeverWithoutTask,
+    /// A `While` that can never exit, because neither its condition nor its body can ever fail.
+    EndlessWhile,
+}
+
+impl std::fmt::Display for BehaveLint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self { kind, path, node } = self;
+        match kind {
+            BehaveLintKind::UnreachableChildren { count: 1 } => {
+                write!(f, "{path}: {node} can never run")
+            }
+            BehaveLintKind::UnreachableChildren { count } => write!(
+                f,
+                "{path}: {node} and the {} siblings after it can never run",
+                count - 1
+            ),
+            BehaveLintKind::DoubleInvert => write!(f, "{path}: {node} of an Invert has no effect"),
+            BehaveLintKind::EmptyControlFlow => write!(f, "{path}: {node} has no children"),
+            BehaveLintKind::ForeverWithoutTask => {
+                write!(
+                    f,
+                    "{path}: {node} has no task beneath it, so loops without waiting"
+                )
+            }
+            BehaveLintKind::EndlessWhile => {
+                write!(f, "{path}: {node} can never fail, so never exits")
+            }
+        }
+    }
+}
+
+impl BehaveTree {
+    /// Checks a tree definition for branches that can never run, or that loop forever without
+    /// waiting on anything. Unlike [`BehaveTree::verify`], these trees are still valid to run.
+    ///
+    /// Spawned entities and triggers are assumed to be able to both succeed and fail.
+    pub fn lint(tree: &Tree<Behave>) -> Vec<BehaveLint> {
+        let mut lints = Vec::new();
+        lint_node(tree.root(), "root".to_string(), &mut lints);
+        lints
+    }
+}
+
+fn lint_node(node: NodeRef<Behave>, path: String, lints: &mut Vec<BehaveLint>) {
+    let mut lint = |kind, path: &str, node: &Behave| {
+        lints.push(BehaveLint {
+            kind,
+            path: path.to_string(),
+            node: node.to_string(),
+        })
+    };
+    let children = node.children().collect::<Vec<_>>();
+    let child_paths = children
+        .iter()
+        .enumerate()
+        .map(|(i, child)| child_path(&path, child.value().path_name(), i, children.len()))
+        .collect::<Vec<_>>();
+    match node.value().unlabelled() {
+        Behave::Sequence | Behave::Fallback if children.is_empty() => {
+            lint(BehaveLintKind::EmptyControlFlow, &path, node.value());
+        }
+        b @ (Behave::Sequence | Behave::Fallback) => {
+            let ends_early = |child: &NodeRef<Behave>| match b {
+                Behave::Sequence => !can_succeed(*child),
+                _ => !can_fail(*child),
+            };
+            if let Some(i) = children.iter().position(ends_early)
+                && i + 1 < children.len()
+            {
+                lint(
+                    BehaveLintKind::UnreachableChildren {
+                        count: children.len() - i - 1,
+                    },
+                    &child_paths[i + 1],
+                    children[i + 1].value(),
+                );
+            }
+        }
+        Behave::Invert => {
+            if let [child] = children.as_slice()
+                && matches!(child.value().unlabelled(), Behave::Invert)
+            {
+                lint(BehaveLintKind::DoubleInvert, &child_paths[0], child.value());
+            }
+        }
+        Behave::Forever if !has_task(node) => {
+            lint(BehaveLintKind::ForeverWithoutTask, &path, node.value());
+        }
+        Behave::While if !children.iter().any(|c| can_fail(*c)) => {
+            lint(BehaveLintKind::EndlessWhile, &path, node.value());
+        }
+        _ => {}
+    }
+    for (child, child_path) in children.into_iter().zip(child_paths) {
+        lint_node(child, child_path, lints);
+    }
+}
+
+/// Is there a leaf beneath this node that waits on something?
+fn has_task(node: NodeRef<Behave>) -> bool {
+    node.descendants().any(|n| {
+        matches!(
+            n.value().unlabelled(),
+            Behave::Wait(_)
+                | Behave::DynamicEntity { .. }
+                | Behave::TriggerReq(_)
+                | Behave::Action(_)
+        )
+    })
+}
+
+fn can_succeed(node: NodeRef<Behave>) -> bool {
+    can_succeed_as(node.value(), node)
+}
+
+/// Can `node` succeed, if it's a `behave` node? Labelled nodes are checked as the node they label.
+fn can_succeed_as(behave: &Behave, node: NodeRef<Behave>) -> bool {
+    let mut children = node.children();
+    match behave {
+        Behave::AlwaysSucceed | Behave::Wait(_) => true,
+        Behave::AlwaysFail => false,
+        Behave::DynamicEntity { .. } | Behave::TriggerReq(_) | Behave::Action(_) => true,
+        // empty sequences and fallbacks succeed
+        Behave::Sequence => children.all(can_succeed),
+        Behave::Fallback => node.children().next().is_none() || children.any(can_succeed),
+        Behave::Invert => children.any(can_fail),
+        // loops only ever end by failing
+        Behave::Forever | Behave::While => false,
+        Behave::IfThen => {
+            let (Some(cond), Some(then)) = (children.next(), children.next()) else {
+                return false;
+            };
+            let otherwise = children.next();
+            (can_succeed(cond) && can_succeed(then))
+                || (can_fail(cond) && otherwise.is_some_and(can_succeed))
+        }
+        Behave::Labelled { behave, .. } => can_succeed_as(behave, node),
+    }
+}
+
+fn can_fail(node: NodeRef<Behave>) -> bool {
+    can_fail_as(node.value(), node)
+}
+
+/// Can `node` fail, if it's a `behave` node? Labelled nodes are checked as the node they label.
+fn can_fail_as(behave: &Behave, node: NodeRef<Behave>) -> bool {
+    let mut children = node.children();
+    match behave {
+        Behave::AlwaysSucceed | Behave::Wait(_) => false,
+        Behave::AlwaysFail => true,
+        Behave::DynamicEntity { .. } | Behave::TriggerReq(_) | Behave::Action(_) => true,
+        Behave::Sequence => children.any(can_fail),
+        Behave::Fallback => node.children().next().is_some() && children.all(can_fail),
+        Behave::Invert => children.any(can_succeed),
+        Behave::Forever => false,
+        Behave::While => children.any(can_fail),
+        Behave::IfThen => {
+            let (Some(cond), Some(then)) = (children.next(), children.next()) else {
+                return false;
+            };
+            let otherwise = children.next();
+            (can_succeed(cond) && can_fail(then))
+                || (can_fail(cond) && otherwise.is_none_or(can_fail))
+        }
+        Behave::Labelled { behave, .. } => can_fail_as(behave, node),
+    }
+}
@@ -0,0 +1,93 @@
+//! Opt-in events triggered on the tree entity when nodes start and finish.
+use crate::{BehaveNodeStatus, NodeLabels, prelude::*};
+use bevy::prelude::*;
+
+/// Which nodes of a tree trigger [`BehaveNodeStarted`] and [`BehaveNodeFinished`], set with
+/// [`BehaveTree::with_node_events`]. Off by default, so trees that don't need the events don't
+/// pay for them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BehaveNodeEvents {
+    /// No node events.
+    #[default]
+    Off,
+    /// Every node triggers events.
+    All,
+    /// Only nodes labelled with `Behave::named` trigger events.
+    Labelled,
+}
+
+impl BehaveNodeEvents {
+    pub(crate) fn emits(&self, node: NodeId, labels: &NodeLabels) -> bool {
+        match self {
+            BehaveNodeEvents::Off => false,
+            BehaveNodeEvents::All => true,
+            BehaveNodeEvents::Labelled => labels.contains_key(&node),
+        }
+    }
+}
+
+/// Triggered on the tree entity when a node starts running, including each time it runs again,
+/// eg in a loop. Enable with [`BehaveTree::with_node_events`].
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_behave::prelude::*;
+/// fn on_node_started(trigger: On<BehaveNodeStarted>) {
+///     if trigger.label == Some("flee") {
+///         info!("{} started fleeing", trigger.entity);
+///     }
+/// }
+/// ```
+#[derive(EntityEvent, Debug, Clone, Copy, PartialEq)]
+pub struct BehaveNodeStarted {
+    /// The tree entity.
+    pub entity: Entity,
+    /// The node that started.
+    pub node: NodeId,
+    /// The node's label, from `Behave::named`.
+    pub label: Option<&'static str>,
+}
+
+/// Triggered on the tree entity when a node finishes with success or failure. Not triggered for
+/// nodes stopped before finishing, eg when the tree is aborted. Enable with
+/// [`BehaveTree::with_node_events`].
+#[derive(EntityEvent, Debug, Clone, Copy, PartialEq)]
+pub struct BehaveNodeFinished {
+    /// The tree entity.
+    pub entity: Entity,
+    /// The node that finished.
+    pub node: NodeId,
+    /// The node's label, from `Behave::named`.
+    pub label: Option<&'static str>,
+    /// `BehaveStatus::Success` or `BehaveStatus::Failure`.
+    pub status: BehaveStatus,
+}
+
+/// Ticks a node with `tick`, triggering the events for it starting and finishing. Started is
+/// triggered before the node ticks, so it comes before the events of its children.
+pub(crate) fn tick_with_node_events<E>(
+    commands: &mut Commands,
+    bt_entity: Entity,
+    node: NodeId,
+    label: Option<&'static str>,
+    before: Option<BehaveNodeStatus>,
+    tick: impl FnOnce(&mut Commands) -> Result<BehaveNodeStatus, E>,
+) -> Result<BehaveNodeStatus, E> {
+    if before.is_none() {
+        commands.trigger(BehaveNodeStarted {
+            entity: bt_entity,
+            node,
+            label,
+        });
+    }
+    let after = tick(commands)?;
+    let done = |s| matches!(s, BehaveNodeStatus::Success | BehaveNodeStatus::Failure);
+    if !before.is_some_and(done) && done(after) {
+        commands.trigger(BehaveNodeFinished {
+            entity: bt_entity,
+            node,
+            label,
+            status: after.into(),
+        });
+    }
+    Ok(after)
+}
@@ -1,12 +1,19 @@
 use crate::{
-    BehaveNode, BehaveNodeStatus, EntityTaskStatus, TriggerTaskStatus,
+    BehaveNode, BehaveNodeStatus, EntityTaskStatus, NodeLabels, RunningTask, TriggerTaskStatus,
+    async_task::poll_async_tasks,
     behave_trigger::{DynamicTrigger, DynamicTriggerCommand},
+    error::{invalid_node_error, node_path, tree_errors},
+    halt_task,
     prelude::*,
+    rollback::{sync_restored_markers, tick_behave_clock},
     tick_node,
+    watchdog::check_watchdogs,
 };
 use bevy::ecs::system::SystemState;
+use bevy::ecs::world::DeferredWorld;
 use bevy::prelude::*;
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 // use bevy::app::FixedPreUpdate;
 use bevy::ecs::intern::Interned;
 use bevy::ecs::schedule::{ScheduleLabel, SystemSet};
@@ -23,6 +30,22 @@ pub struct BehavePlugin {
     schedule: Interned<dyn ScheduleLabel>,
     /// if true, use an exclusive mut World system to tick trees, to avoid next-frame delays on triggers
     synchronous: bool,
+    /// if set, trees are ticked in entity order using a simulated clock advancing this much per tick
+    rollback_secs_per_tick: Option<f32>,
+    settings: BehaveSettings,
+}
+
+/// Plugin options that observers and systems need at runtime.
+#[derive(Resource, Debug, Clone, Default)]
+pub(crate) struct BehaveSettings {
+    /// The result reported for a task entity despawned before it reported one.
+    pub(crate) despawned_task_success: bool,
+    /// Check triggers have observers when trees spawn and triggers fire.
+    pub(crate) check_trigger_observers: bool,
+    /// Watchdog for trees without their own `BehaveWatchdog` component.
+    pub(crate) watchdog: Option<BehaveWatchdog>,
+    /// The schedule trees are ticked in, for systems added with `BehaveAppExt`.
+    pub(crate) schedule: Option<Interned<dyn ScheduleLabel>>,
 }
 
 impl BehavePlugin {
@@ -31,6 +54,11 @@ impl BehavePlugin {
         Self {
             schedule: schedule.intern(),
             synchronous: false,
+            rollback_secs_per_tick: None,
+            settings: BehaveSettings {
+                schedule: Some(schedule.intern()),
+                ..default()
+            },
         }
     }
     /// Return the schedule this plugin will run in.
@@ -44,6 +72,38 @@ impl BehavePlugin {
         self.synchronous = true;
         self
     }
+
+    /// Enables rollback mode, for deterministic netcode.
+    ///
+    /// Trees are ticked in `Entity` order, and the [`BehaveClock`] advances by `secs_per_tick`
+    /// every time the `BehaveSet` runs, instead of following `Time::elapsed_secs`.
+    /// Combine with [`BehaveTree::snapshot`] and [`BehaveTree::restore`] to resimulate.
+    pub fn with_rollback(mut self, secs_per_tick: f32) -> Self {
+        self.rollback_secs_per_tick = Some(secs_per_tick);
+        self
+    }
+
+    /// The result to report when a task entity is despawned (or loses its `BehaveCtx`) before
+    /// reporting a result itself. Defaults to failure.
+    pub fn with_despawned_task_result(mut self, success: bool) -> Self {
+        self.settings.despawned_task_success = success;
+        self
+    }
+
+    /// Debug mode that checks every `Behave::trigger` has an observer for its `BehaveTrigger<T>`,
+    /// when a tree is spawned and each time a trigger fires. Without an observer, a trigger node
+    /// waits forever, so the tree is stopped with a [`BehaveError`] naming the trigger type.
+    pub fn with_trigger_checks(mut self) -> Self {
+        self.settings.check_trigger_observers = true;
+        self
+    }
+
+    /// Watch every tree for task nodes left waiting on a result for too long, see
+    /// [`BehaveWatchdog`]. A `BehaveWatchdog` component on a tree entity overrides this.
+    pub fn with_watchdog(mut self, watchdog: BehaveWatchdog) -> Self {
+        self.settings.watchdog = Some(watchdog);
+        self
+    }
 }
 
 impl Default for BehavePlugin {
@@ -57,12 +117,33 @@ impl Plugin for BehavePlugin {
     fn build(&self, app: &mut App) {
         app.configure_sets(self.schedule, BehaveSet);
         app.register_type::<BehaveTimeout>();
+        app.register_type::<BehaveClock>();
+        app.register_type::<BehaveOnFinish>();
+        app.register_type::<BehaveWatchdog>();
         app.init_resource::<InterruptState>();
+        app.init_resource::<BehaveTriggerRegistry>();
+        app.insert_resource(BehaveClock::new(self.rollback_secs_per_tick));
+        app.insert_resource(self.settings.clone());
 
+        app.add_systems(
+            self.schedule,
+            (tick_behave_clock, sync_restored_markers)
+                .chain()
+                .in_set(BehaveSet)
+                .before(tick_timeout_components)
+                .before(tick_interrupt_components),
+        );
         app.add_systems(
             self.schedule,
             (tick_timeout_components, tick_interrupt_components).in_set(BehaveSet),
         );
+        app.add_systems(
+            self.schedule,
+            (check_watchdogs, poll_async_tasks)
+                .after(tick_behave_clock)
+                .before(tick_timeout_components)
+                .in_set(BehaveSet),
+        );
 
         if self.synchronous {
             warn!("Using experimental synchronous tree ticking");
@@ -80,6 +161,9 @@ impl Plugin for BehavePlugin {
         }
 
         app.add_observer(on_tick_timeout_added);
+        if self.settings.check_trigger_observers {
+            app.add_observer(check_spawned_tree_triggers);
+        }
         app.add_observer(handle_interrupt_responses);
         // adds a global observer to listen for status report events
         app.add_plugins(crate::ctx::plugin);
@@ -111,53 +195,141 @@ pub struct BehaveSupervisorEntity(pub Entity);
 #[derive(Component)]
 pub(crate) struct BehaveAwaitingTrigger;
 
-#[allow(clippy::type_complexity)]
-fn tick_trees(
-    mut query: Query<
-        (
-            Entity,
-            &mut BehaveTree,
-            Option<&ChildOf>,
-            &BehaveTargetEntity,
-            Option<&BehaveSupervisorEntity>,
-        ),
-        (Without<BehaveAwaitingTrigger>, Without<BehaveFinished>),
-    >,
-    q_parents: Query<&ChildOf>,
-    mut commands: Commands,
-    time: Res<Time>,
-) {
-    for (bt_entity, mut bt, opt_parent, target_entity, opt_sup_entity) in query.iter_mut() {
-        let target_entity = match target_entity {
-            BehaveTargetEntity::Parent => opt_parent
-                .map(|p| p.parent())
-                .unwrap_or(Entity::PLACEHOLDER),
-            BehaveTargetEntity::Entity(e) => *e,
-            BehaveTargetEntity::RootAncestor => q_parents.root_ancestor(bt_entity),
-        };
-        let tick_ctx = TickCtx::new(bt_entity, target_entity, time.elapsed_secs())
-            .with_optional_sup_entity(opt_sup_entity.map(|c| c.0));
-        let tick_result = bt.tick(&mut commands, &tick_ctx);
-        match tick_result {
-            BehaveNodeStatus::AwaitingTrigger => {
-                commands.entity(bt_entity).insert(BehaveAwaitingTrigger);
-            }
-            BehaveNodeStatus::Success => {
-                commands.entity(bt_entity).insert(BehaveFinished(true));
+type TickTreesQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut BehaveTree,
+        Option<&'static ChildOf>,
+        &'static BehaveTargetEntity,
+        Option<&'static BehaveSupervisorEntity>,
+        Option<&'static BehaveOnFinish>,
+    ),
+    (
+        Without<BehaveAwaitingTrigger>,
+        Without<BehaveFinished>,
+        Without<BehavePaused>,
+    ),
+>;
+
+type TickTreesItem<'a> = (
+    Entity,
+    Mut<'a, BehaveTree>,
+    Option<&'a ChildOf>,
+    &'a BehaveTargetEntity,
+    Option<&'a BehaveSupervisorEntity>,
+    Option<&'a BehaveOnFinish>,
+);
+
+/// Runs `f` for every tree in the query.
+/// When the clock is simulated (rollback mode) trees are visited in `Entity` order, so the
+/// commands they queue are deterministic.
+fn for_each_tree(query: &mut TickTreesQuery, clock: &BehaveClock, f: impl FnMut(TickTreesItem)) {
+    if clock.is_simulated() {
+        let mut trees = query.iter_mut().collect::<Vec<_>>();
+        trees.sort_unstable_by_key(|(bt_entity, ..)| *bt_entity);
+        trees.into_iter().for_each(f);
+    } else {
+        query.iter_mut().for_each(f);
+    }
+}
+
+/// Ticks a single tree, and inserts the marker components matching the result.
+fn tick_tree(
+    (bt_entity, mut bt, opt_parent, target_entity, opt_sup_entity, opt_on_finish): TickTreesItem,
+    q_parents: &Query<&ChildOf>,
+    commands: &mut Commands,
+    clock: &BehaveClock,
+    mode: &str,
+) -> BehaveNodeStatus {
+    let target_entity = match target_entity {
+        BehaveTargetEntity::Parent => opt_parent
+            .map(|p| p.parent())
+            .unwrap_or(Entity::PLACEHOLDER),
+        BehaveTargetEntity::Entity(e) => *e,
+        BehaveTargetEntity::RootAncestor => q_parents.root_ancestor(bt_entity),
+    };
+    let tick_ctx = TickCtx::new(bt_entity, target_entity, clock.elapsed_secs())
+        .with_optional_sup_entity(opt_sup_entity.map(|c| c.0))
+        .with_clock_tick(clock.tick())
+        .with_labels(bt.labels.clone())
+        .with_node_events(bt.node_events);
+    let tick_result = match bt.tick(commands, &tick_ctx) {
+        Ok(tick_result) => tick_result,
+        Err(e) => {
+            stop_tree(commands, bt_entity, bt.stop(), e);
+            return BehaveNodeStatus::Failure;
+        }
+    };
+    match tick_result {
+        BehaveNodeStatus::AwaitingTrigger => {
+            commands.entity(bt_entity).insert(BehaveAwaitingTrigger);
+        }
+        BehaveNodeStatus::Success | BehaveNodeStatus::Failure => {
+            let success = tick_result == BehaveNodeStatus::Success;
+            let failure = bt.failure();
+            if bt.logging
+                && let Some(failure) = &failure
+            {
+                info!("Behaviour tree {bt_entity} failed, {failure}");
             }
-            BehaveNodeStatus::Failure => {
-                commands.entity(bt_entity).insert(BehaveFinished(false));
+            commands.trigger(BehaveTreeFinished {
+                entity: bt_entity,
+                success,
+                failure: failure.clone(),
+            });
+            match opt_on_finish.copied().unwrap_or_default() {
+                BehaveOnFinish::Keep => {
+                    insert_finished(commands, bt_entity, success, failure);
+                }
+                BehaveOnFinish::Restart => bt.reset(),
+                BehaveOnFinish::DespawnTree => {
+                    commands.entity(bt_entity).try_despawn();
+                }
+                BehaveOnFinish::DespawnTarget => {
+                    insert_finished(commands, bt_entity, success, failure);
+                    if target_entity != Entity::PLACEHOLDER {
+                        commands.entity(target_entity).try_despawn();
+                    }
+                }
             }
-            BehaveNodeStatus::RunningTimer => {}
-            BehaveNodeStatus::Running => {}
-            BehaveNodeStatus::PendingReset => {}
-        }
-        if bt.logging && tick_result != BehaveNodeStatus::RunningTimer {
-            info!("ticked tree(async): {bt_entity}\n{}", bt.tree);
         }
+        BehaveNodeStatus::RunningTimer => {}
+        BehaveNodeStatus::Running => {}
+        BehaveNodeStatus::PendingReset => {}
+    }
+    if bt.logging && tick_result != BehaveNodeStatus::RunningTimer {
+        info!("ticked tree ({mode}): {bt_entity}\n{}", bt.tree);
+    }
+    tick_result
+}
+
+/// Inserts `BehaveFinished`, and `BehaveFailure` if we know why the tree failed.
+fn insert_finished(
+    commands: &mut Commands,
+    bt_entity: Entity,
+    success: bool,
+    failure: Option<BehaveFailure>,
+) {
+    let mut e = commands.entity(bt_entity);
+    e.insert(BehaveFinished(success));
+    if let Some(failure) = failure {
+        e.insert(failure);
     }
 }
 
+fn tick_trees(
+    mut query: TickTreesQuery,
+    q_parents: Query<&ChildOf>,
+    mut commands: Commands,
+    clock: Res<BehaveClock>,
+) {
+    for_each_tree(&mut query, &clock, |item| {
+        tick_tree(item, &q_parents, &mut commands, &clock, "async");
+    });
+}
+
 const SANITY_LOOP_LIMIT: usize = 1000;
 
 /// An exclusive mut World system version of tick_trees.
@@ -168,28 +340,16 @@ const SANITY_LOOP_LIMIT: usize = 1000;
 /// This means that if you have a tree with a Behave::trigger(Whatever), which returns immediately,
 /// (eg, the observer reports the status via commands.trigger), it will be re-ticked immediately,
 /// and progress to the next node, without any next-frame delay.
-#[allow(clippy::type_complexity)]
 fn tick_trees_sync(
     world: &mut World,
-    params: &mut SystemState<(
-        Query<
-            (
-                Entity,
-                &mut BehaveTree,
-                Option<&ChildOf>,
-                &BehaveTargetEntity,
-                Option<&BehaveSupervisorEntity>,
-            ),
-            (Without<BehaveAwaitingTrigger>, Without<BehaveFinished>),
-        >,
-        Query<&ChildOf>,
-        Commands,
-        Res<Time>,
-    )>,
+    params: &mut SystemState<(TickTreesQuery, Query<&ChildOf>, Commands, Res<BehaveClock>)>,
 ) {
     let mut sanity_counter = 0;
+    // trees restarted by `BehaveOnFinish::Restart` run again next frame, rather than looping
+    // here until the sanity limit if they finish within a single tick.
+    let mut restarted = HashSet::new();
     loop {
-        let (mut query, q_parents, mut commands, time) = params.get_mut(world);
+        let (mut query, q_parents, mut commands, clock) = params.get_mut(world);
         if query.is_empty() {
             return;
         }
@@ -201,42 +361,29 @@ fn tick_trees_sync(
         }
         // info!("Ticking {} trees (sync)", query.iter().count());
 
+        // trees that are waiting on a timer will always be happy to tick, but they don't need to
+        // be ticked more than once per frame, since the time won't advance until the next frame.
+        // so RunningTimer results don't increment the trees_processed counter.
         let mut trees_processed = 0;
-        for (bt_entity, mut bt, opt_parent, target_entity, opt_sup_entity) in query.iter_mut() {
-            let target_entity = match target_entity {
-                BehaveTargetEntity::Parent => opt_parent
-                    .map(|p| p.parent())
-                    .unwrap_or(Entity::PLACEHOLDER),
-                BehaveTargetEntity::Entity(e) => *e,
-                BehaveTargetEntity::RootAncestor => q_parents.root_ancestor(bt_entity),
-            };
-            let tick_ctx = TickCtx::new(bt_entity, target_entity, time.elapsed_secs())
-                .with_optional_sup_entity(opt_sup_entity.map(|c| c.0));
-            let tick_result = bt.tick(&mut commands, &tick_ctx);
-            match tick_result {
-                BehaveNodeStatus::AwaitingTrigger => {
-                    commands.entity(bt_entity).insert(BehaveAwaitingTrigger);
-                }
-                BehaveNodeStatus::Success => {
-                    commands.entity(bt_entity).insert(BehaveFinished(true));
-                }
-                BehaveNodeStatus::Failure => {
-                    commands.entity(bt_entity).insert(BehaveFinished(false));
-                }
-                BehaveNodeStatus::RunningTimer => {}
-                BehaveNodeStatus::Running => {}
-                BehaveNodeStatus::PendingReset => {}
+        for_each_tree(&mut query, &clock, |item| {
+            let bt_entity = item.0;
+            if restarted.contains(&bt_entity) {
+                return;
             }
-            if bt.logging && tick_result != BehaveNodeStatus::RunningTimer {
-                info!("ticked tree (sync): {bt_entity}\n{}", bt.tree);
+            let restart = item.5 == Some(&BehaveOnFinish::Restart);
+            let result = tick_tree(item, &q_parents, &mut commands, &clock, "sync");
+            if restart
+                && matches!(
+                    result,
+                    BehaveNodeStatus::Success | BehaveNodeStatus::Failure
+                )
+            {
+                restarted.insert(bt_entity);
             }
-            // trees that are waiting on a timer will always be happy to tick, but they don't need to
-            // be ticked more than once per frame, since the time won't advance until the next frame.
-            // so RunningTimer results don't increment the trees_processed counter.
-            if tick_result != BehaveNodeStatus::RunningTimer {
+            if result != BehaveNodeStatus::RunningTimer {
                 trees_processed += 1;
             }
-        }
+        });
         params.apply(world);
         if trees_processed == 0 {
             // either no trees, or all trees are running timers and don't need to be re-ticked
@@ -253,18 +400,29 @@ fn tick_trees_sync(
 #[require(BehaveTargetEntity)]
 #[require(Name::new("BehaveTree"))]
 pub struct BehaveTree {
-    tree: Tree<BehaveNode>,
-    logging: bool,
+    pub(crate) tree: Tree<BehaveNode>,
+    pub(crate) logging: bool,
+    /// The result of the most recent tick, used to restore the marker components after a rollback.
+    pub(crate) last_tick: Option<BehaveNodeStatus>,
+    /// Set by `restore`, so the marker components are synced before the next tick.
+    pub(crate) restored: bool,
+    /// Task node runs already flagged by the watchdog, as `(node, generation)`.
+    pub(crate) overdue: Vec<(NodeId, u32)>,
+    /// Labels given with `Behave::named`, shared with the tick contexts.
+    pub(crate) labels: Arc<NodeLabels>,
+    /// Which nodes trigger `BehaveNodeStarted` and `BehaveNodeFinished`.
+    pub(crate) node_events: BehaveNodeEvents,
 }
 impl std::fmt::Display for BehaveTree {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        walk_tree(self.tree.root(), 0, f)?;
+        walk_tree(self.tree.root(), &self.labels, 0, f)?;
         Ok(())
     }
 }
 
 fn walk_tree(
     node: NodeRef<BehaveNode>,
+    labels: &NodeLabels,
     depth: usize,
     f: &mut std::fmt::Formatter<'_>,
 ) -> std::fmt::Result {
@@ -272,34 +430,16 @@ fn walk_tree(
         write!(f, " ")?;
     }
     write!(f, "* ")?;
-    writeln!(f, "{}  [{:?}]", node.value(), node.id())?;
+    match labels.get(&node.id()) {
+        Some(label) => writeln!(f, "{}  [{label}]", node.value())?,
+        None => writeln!(f, "{}  [{:?}]", node.value(), node.id())?,
+    }
     for child in node.children() {
-        walk_tree(child, depth + 1, f)?;
+        walk_tree(child, labels, depth + 1, f)?;
     }
     Ok(())
 }
 
-/// Verifies that nodes have the appropriate number of children etc.
-fn verify_tree(node: &NodeRef<Behave>) -> bool {
-    let children = node.children().collect::<Vec<_>>();
-    let n = node.value();
-    let range = n.permitted_children();
-    if !range.contains(&children.len()) {
-        error!(
-            "⁉️  Node {n} has {} children! Valid range is: {range:?}",
-            children.len(),
-        );
-        false
-    } else {
-        for child in children.iter() {
-            if !verify_tree(child) {
-                return false;
-            }
-        }
-        true
-    }
-}
-
 impl TickCtx {
     /// Create a new TickCtx with the given behaviour tree entity and target entity.
     pub(crate) fn new(bt_entity: Entity, target_entity: Entity, elapsed_secs: f32) -> Self {
@@ -308,7 +448,10 @@ impl TickCtx {
             target_entity,
             supervisor_entity: None,
             elapsed_secs,
+            clock_tick: 0,
             logging: false,
+            labels: default(),
+            node_events: default(),
         }
     }
     /// Set the optional supervisor entity that is controlling the behaviour tree.
@@ -318,6 +461,21 @@ impl TickCtx {
         self
     }
 
+    pub(crate) fn with_clock_tick(mut self, clock_tick: u64) -> Self {
+        self.clock_tick = clock_tick;
+        self
+    }
+
+    pub(crate) fn with_labels(mut self, labels: Arc<NodeLabels>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    pub(crate) fn with_node_events(mut self, node_events: BehaveNodeEvents) -> Self {
+        self.node_events = node_events;
+        self
+    }
+
     #[allow(unused)]
     pub(crate) fn with_logging(mut self, logging: bool) -> Self {
         self.logging = logging;
@@ -341,6 +499,12 @@ pub(crate) struct TickCtx {
     pub(crate) supervisor_entity: Option<Entity>,
     /// Bevy's Time res elapsed_secs
     pub(crate) elapsed_secs: f32,
+    /// `BehaveClock::tick`, so actions are ticked at most once per frame.
+    pub(crate) clock_tick: u64,
+    /// The tree's node labels, for the contexts created while ticking.
+    pub(crate) labels: Arc<NodeLabels>,
+    /// Which nodes trigger `BehaveNodeStarted` and `BehaveNodeFinished`.
+    pub(crate) node_events: BehaveNodeEvents,
 }
 
 impl BehaveTree {
@@ -350,23 +514,51 @@ impl BehaveTree {
     ///
     /// # Panics
     /// An invalid tree will cause a panic here.
-    /// Use BehaveTree::verify(&tree) to verify your tree definition first.
+    /// Use BehaveTree::try_new(tree) to handle an invalid tree definition instead.
     pub fn new(tree: Tree<Behave>) -> Self {
-        if !Self::verify(&tree) {
-            panic!("Invalid tree");
+        match Self::try_new(tree) {
+            Ok(bt) => bt,
+            Err(e) => panic!("Invalid tree: {e}"),
+        }
+    }
+
+    /// Creates a BehaveTree, or returns the first problem found with the tree definition.
+    pub fn try_new(tree: Tree<Behave>) -> Result<Self, BehaveTreeError> {
+        if let Some(e) = Self::verify_errors(&tree).into_iter().next() {
+            return Err(e);
         }
+        let labels = tree
+            .nodes()
+            .filter_map(|n| n.value().label().map(|label| (n.id(), label)))
+            .collect();
         // convert to internal BehaveNode tree
         let tree = tree.map(BehaveNode::new);
-        Self {
+        Ok(Self {
             tree,
             logging: false,
-        }
+            last_tick: None,
+            restored: false,
+            overdue: Vec::new(),
+            labels: Arc::new(labels),
+            node_events: BehaveNodeEvents::Off,
+        })
     }
 
     /// Checks the tree definition is valid by verifying that each node has the correct
-    /// number of children.
+    /// number of children. Each problem found is logged as an error.
     pub fn verify(tree: &Tree<Behave>) -> bool {
-        verify_tree(&tree.root())
+        let errors = Self::verify_errors(tree);
+        for e in errors.iter() {
+            error!("⁉️  Invalid tree, {e}");
+        }
+        errors.is_empty()
+    }
+
+    /// Returns every problem with the tree definition, in depth-first order.
+    pub fn verify_errors(tree: &Tree<Behave>) -> Vec<BehaveTreeError> {
+        let mut errors = Vec::new();
+        tree_errors(tree.root(), "root".to_string(), &mut errors);
+        errors
     }
 
     /// Should verbose logging be enabled? (typically just for debugging).
@@ -375,20 +567,159 @@ impl BehaveTree {
         self
     }
 
-    fn tick(&mut self, commands: &mut Commands, tick_ctx: &TickCtx) -> BehaveNodeStatus {
+    /// Triggers [`BehaveNodeStarted`] and [`BehaveNodeFinished`] on the tree entity for all
+    /// nodes, or just labelled ones:
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_behave::prelude::*;
+    /// # fn setup(mut commands: Commands) {
+    /// let tree = behave! {
+    ///     Behave::Fallback => {
+    ///         Behave::Wait(1.0).named("flee"),
+    ///         Behave::Wait(2.0),
+    ///     }
+    /// };
+    /// commands
+    ///     .spawn(BehaveTree::new(tree).with_node_events(BehaveNodeEvents::Labelled))
+    ///     .observe(|trigger: On<BehaveNodeStarted>| info!("started {:?}", trigger.label));
+    /// # }
+    /// ```
+    pub fn with_node_events(mut self, node_events: BehaveNodeEvents) -> Self {
+        self.node_events = node_events;
+        self
+    }
+
+    fn tick(
+        &mut self,
+        commands: &mut Commands,
+        tick_ctx: &TickCtx,
+    ) -> Result<BehaveNodeStatus, BehaveTreeError> {
         let mut node = self.tree.root_mut();
-        tick_node(&mut node, commands, tick_ctx)
+        match tick_node(&mut node, commands, tick_ctx) {
+            Ok(result) => {
+                self.last_tick = Some(result);
+                Ok(result)
+            }
+            Err(node_id) => {
+                self.last_tick = Some(BehaveNodeStatus::Failure);
+                Err(invalid_node_error(&self.tree, &self.labels, node_id))
+            }
+        }
+    }
+
+    /// Stops the tree with a failure, returning any running task entities to be halted.
+    pub(crate) fn stop(&mut self) -> Vec<RunningTask> {
+        self.last_tick = Some(BehaveNodeStatus::Failure);
+        self.take_running_tasks()
+    }
+
+    /// The error for a trigger node with no observers.
+    pub(crate) fn unobserved_trigger_error(
+        &self,
+        node_id: NodeId,
+        trigger: &DynamicTrigger,
+    ) -> BehaveTreeError {
+        BehaveTreeError::UnobservedTrigger {
+            path: self
+                .tree
+                .get(node_id)
+                .map(|node| node_path(node, &self.labels))
+                .unwrap_or_default(),
+            trigger: trigger.type_name().to_string(),
+        }
+    }
+
+    /// Halts every task that's still running, see [`BehaveHalted`].
+    pub(crate) fn halt_running_tasks(&mut self, commands: &mut Commands) {
+        for task in self.take_running_tasks() {
+            halt_task(commands, task);
+        }
+    }
+
+    /// Stops tracking every task that's still running, returning them to be halted.
+    pub(crate) fn take_running_tasks(&mut self) -> Vec<RunningTask> {
+        self.tree
+            .values_mut()
+            .filter_map(BehaveNode::halt)
+            .collect()
+    }
+
+    /// Resets every node, so the tree runs again from the start.
+    /// Running tasks must be halted first, with `take_running_tasks`.
+    pub(crate) fn reset(&mut self) {
+        for node in self.tree.values_mut() {
+            node.reset();
+        }
+        self.last_tick = None;
+    }
+
+    /// Is this task entity still running the task node, without having reported a result?
+    pub(crate) fn is_running_task(&self, node_id: NodeId, task_entity: Entity) -> bool {
+        self.tree.get(node_id).is_some_and(|node| {
+            matches!(
+                node.value(),
+                BehaveNode::DynamicEntity {
+                    task_status: EntityTaskStatus::Started(e),
+                    ..
+                } if *e == task_entity
+            )
+        })
+    }
+
+    /// Finds the first node with this label, from `Behave::named`, in depth-first order.
+    pub fn find(&self, label: &str) -> Option<NodeId> {
+        self.tree
+            .nodes()
+            .map(|n| n.id())
+            .find(|id| self.labels.get(id).is_some_and(|l| *l == label))
+    }
+
+    /// The node's label, if it was given one with `Behave::named`.
+    pub fn label(&self, node_id: NodeId) -> Option<&'static str> {
+        self.labels.get(&node_id).copied()
+    }
+
+    /// The node's `Display` in the tree definition, with its label if it has one.
+    pub(crate) fn node_name(&self, node_id: NodeId) -> String {
+        let name = self
+            .tree
+            .get(node_id)
+            .map_or_else(|| format!("{node_id:?}"), |n| n.value().definition_name());
+        match self.labels.get(&node_id) {
+            Some(label) => format!("{name} \"{label}\""),
+            None => name,
+        }
+    }
+
+    /// Is this context for the current run of its task node, which is still waiting for a
+    /// result? A node that was reset, eg by a restart, isn't waiting until it starts again.
+    pub(crate) fn is_current(&self, ctx: &BehaveCtx) -> bool {
+        self.tree.get(ctx.task_node()).is_none_or(|node| {
+            node.value().task_run().is_none_or(|run| {
+                run.generation == ctx.generation() && node.value().awaiting_result()
+            })
+        })
     }
 
     /// Returns Option<Entity> being an entity that was spawned to run this task node.
     /// (so it can be despawned now that the task is complete)
     /// Will always be none if reporting a result from a TriggerReq node.
-    pub(crate) fn set_node_result(&mut self, ctx: &BehaveCtx, success: bool) -> Option<Entity> {
+    pub(crate) fn set_node_result(
+        &mut self,
+        ctx: &BehaveCtx,
+        result: Result<(), Option<BehaveFailureReason>>,
+    ) -> Option<Entity> {
+        let success = result.is_ok();
         let node_id = ctx.task_node();
-        let mut node = self.tree.get_mut(node_id).unwrap();
+        let Some(mut node) = self.tree.get_mut(node_id) else {
+            warn!("Given result for node ({node_id:?}) that isn't in this tree");
+            return None;
+        };
         let val = node.value();
         match val {
-            BehaveNode::DynamicEntity { task_status, .. } if ctx.is_for_entity() => {
+            BehaveNode::DynamicEntity {
+                task_status, run, ..
+            } if ctx.is_for_entity() => {
                 // extract the entity that was running this node, so we can despawn it
                 let task_entity = match task_status {
                     EntityTaskStatus::Started(e) => Some(*e),
@@ -404,9 +735,12 @@ impl BehaveTree {
                     );
                 }
                 *task_status = EntityTaskStatus::Complete(success);
+                run.failure = result.err().flatten();
                 task_entity
             }
-            BehaveNode::TriggerReq { task_status, .. } => {
+            BehaveNode::TriggerReq {
+                task_status, run, ..
+            } => {
                 if self.logging {
                     debug!(
                         "Setting conditional task for {node_id:?} success to {:?}",
@@ -414,6 +748,7 @@ impl BehaveTree {
                     );
                 }
                 *task_status = TriggerTaskStatus::Complete(success);
+                run.failure = result.err().flatten();
                 None
             }
             _ => {
@@ -424,12 +759,98 @@ impl BehaveTree {
     }
 }
 
+/// Stops a tree with an error, halting its running tasks. Logged once, since finished trees
+/// aren't ticked again.
+fn stop_tree(
+    commands: &mut Commands,
+    bt_entity: Entity,
+    tasks: Vec<RunningTask>,
+    e: BehaveTreeError,
+) {
+    error!("⁉️  Behaviour tree {bt_entity} stopped, {e}");
+    for task in tasks {
+        halt_task(commands, task);
+    }
+    commands
+        .entity(bt_entity)
+        .remove::<BehaveAwaitingTrigger>()
+        .insert((BehaveFinished(false), BehaveError(e)));
+    commands.trigger(BehaveTreeFinished {
+        entity: bt_entity,
+        success: false,
+        failure: None,
+    });
+}
+
+/// [`stop_tree`], from a command.
+pub(crate) fn stop_tree_in_world(world: &mut World, bt_entity: Entity, e: BehaveTreeError) {
+    let tasks = world
+        .get_mut::<BehaveTree>(bt_entity)
+        .map(|mut bt| bt.stop())
+        .unwrap_or_default();
+    stop_tree(&mut world.commands(), bt_entity, tasks, e);
+    world.flush();
+}
+
+/// Stops newly spawned trees that use triggers nobody observes.
+fn check_spawned_tree_triggers(t: On<Add, BehaveTree>, mut world: DeferredWorld) {
+    let bt_entity = t.event().entity;
+    let Some(bt) = world.get::<BehaveTree>(bt_entity) else {
+        return;
+    };
+    let unobserved = bt.tree.nodes().find_map(|node| match node.value() {
+        // the target entity isn't known until the tree ticks, so observers on any entity count
+        BehaveNode::TriggerReq { trigger, .. } if !trigger.is_observed(&world, None) => {
+            Some(bt.unobserved_trigger_error(node.id(), trigger))
+        }
+        _ => None,
+    });
+    let Some(e) = unobserved else {
+        return;
+    };
+    let tasks = world
+        .get_mut::<BehaveTree>(bt_entity)
+        .map(|mut bt| bt.stop())
+        .unwrap_or_default();
+    stop_tree(&mut world.commands(), bt_entity, tasks, e);
+}
+
+/// What to do when a tree finishes, add to the [`BehaveTree`] entity.
+/// Without this component, trees are kept, with a [`BehaveFinished`] component.
+///
+/// Either way, a [`BehaveTreeFinished`] event is triggered on the tree entity first.
+/// Trees stopped by a [`BehaveError`] or aborted with `behave_abort` are always kept.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum BehaveOnFinish {
+    /// Keep the tree entity, and insert `BehaveFinished`.
+    #[default]
+    Keep,
+    /// Reset the tree, to run again from the start on the next tick.
+    Restart,
+    /// Despawn the tree entity (and any children).
+    DespawnTree,
+    /// Despawn the target entity the tree is controlling, see [`BehaveTargetEntity`].
+    /// If the tree is a child of the target, that despawns the tree too.
+    DespawnTarget,
+}
+
+/// Triggered on the tree entity when the tree finishes, or stops with a [`BehaveError`].
+#[derive(EntityEvent, Debug, Clone)]
+pub struct BehaveTreeFinished {
+    /// The tree entity.
+    pub entity: Entity,
+    /// The final result of the tree.
+    pub success: bool,
+    /// Where the failure came from, when the tree failed. `None` if it stopped with an error.
+    pub failure: Option<BehaveFailure>,
+}
+
 /// Will report success or failure after a timeout
 #[derive(Component, Debug, Clone, Reflect)]
 pub struct BehaveTimeout {
     duration: std::time::Duration,
     should_succeed: bool,
-    start_time: f32,
+    pub(crate) start_time: f32,
 }
 
 impl BehaveTimeout {
@@ -450,24 +871,28 @@ impl BehaveTimeout {
 fn on_tick_timeout_added(
     t: On<Add, BehaveTimeout>,
     mut q: Query<&mut BehaveTimeout>,
-    time: Res<Time>,
+    clock: Res<BehaveClock>,
 ) {
     let mut timeout = q.get_mut(t.event().entity).unwrap();
-    timeout.start_time = time.elapsed_secs();
+    timeout.start_time = clock.elapsed_secs();
 }
 
-fn tick_timeout_components(
+pub(crate) fn tick_timeout_components(
     q: Query<(&BehaveTimeout, &BehaveCtx)>,
-    time: Res<Time>,
+    q_paused: Query<(), With<BehavePaused>>,
+    clock: Res<BehaveClock>,
     mut commands: Commands,
 ) {
     for (timeout, ctx) in q.iter() {
-        let elapsed = time.elapsed_secs() - timeout.start_time;
+        if q_paused.contains(ctx.behave_entity()) {
+            continue;
+        }
+        let elapsed = clock.elapsed_secs() - timeout.start_time;
         if elapsed >= timeout.duration.as_secs_f32() {
             if timeout.should_succeed {
                 commands.trigger(ctx.success());
             } else {
-                commands.trigger(ctx.failure());
+                commands.trigger(ctx.failure_because("timed out"));
             }
         }
     }
@@ -547,14 +972,33 @@ struct InterruptContext {
     name: &'static str,
 }
 
+/// The entity a `BehaveTrigger` targets, see [`BehaveTriggerTarget`]. Interrupt checks trigger
+/// with a temporary tree entity, so the setting is read from the tree that spawned them.
+pub(crate) fn trigger_event_target(world: &World, ctx: &BehaveCtx) -> Entity {
+    let bt_entity = world
+        .get_resource::<InterruptState>()
+        .and_then(|state| state.pending_interrupts.get(&ctx.behave_entity()))
+        .map_or(ctx.behave_entity(), |interrupt| {
+            interrupt.ctx.behave_entity()
+        });
+    match world.get::<BehaveTriggerTarget>(bt_entity) {
+        Some(BehaveTriggerTarget::Tree) => bt_entity,
+        _ => ctx.target_entity(),
+    }
+}
+
 fn tick_interrupt_components(
     q: Query<(Entity, &BehaveInterrupt, &BehaveCtx)>,
+    q_paused: Query<(), With<BehavePaused>>,
     mut interrupt_state: ResMut<InterruptState>,
     mut commands: Commands,
 ) {
     interrupt_state.processed_this_frame.clear();
 
     for (entity, interrupt, ctx) in q.iter() {
+        if q_paused.contains(ctx.behave_entity()) {
+            continue;
+        }
         if interrupt_state.processed_this_frame.insert(entity) {
             for InterruptTrigger {
                 dynamic_trigger,
@@ -575,12 +1019,16 @@ fn tick_interrupt_components(
 
                 let interrupt_ctx = BehaveCtx::new_for_trigger(
                     ctx.task_node(),
+                    ctx.generation(),
                     &TickCtx {
                         bt_entity: temp_entity,
                         target_entity: ctx.target_entity(),
                         supervisor_entity: ctx.supervisor_entity(),
                         elapsed_secs: 0.0,
+                        clock_tick: 0,
                         logging: false,
+                        labels: default(),
+                        node_events: default(),
                     },
                 );
 
@@ -592,7 +1040,7 @@ fn tick_interrupt_components(
 
 /// System to handle interrupt trigger responses
 fn handle_interrupt_responses(
-    trigger: Trigger<BehaveStatusReport>,
+    trigger: On<BehaveStatusReport>,
     mut interrupt_state: ResMut<InterruptState>,
     mut commands: Commands,
     q_trees: Query<&BehaveTree>,
@@ -616,11 +1064,11 @@ fn handle_interrupt_responses(
         };
 
         if should_interrupt {
-            if let Ok(tree) = q_trees.get(original_ctx.behave_entity()) {
-                if tree.logging {
-                    let inversion_info = if inverted { " (inverted)" } else { "" };
-                    info!("🛑 Interrupted by: {}{}", name, inversion_info);
-                }
+            if let Ok(tree) = q_trees.get(original_ctx.behave_entity())
+                && tree.logging
+            {
+                let inversion_info = if inverted { " (inverted)" } else { "" };
+                info!("🛑 Interrupted by: {}{}", name, inversion_info);
             }
             commands.trigger(original_ctx.success());
         }
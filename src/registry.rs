@@ -0,0 +1,74 @@
+//! Registry of the `BehaveTrigger<T>` types an app handles.
+use crate::{Behave, prelude::*};
+use bevy::prelude::*;
+use std::any::TypeId;
+
+/// A trigger type registered with [`BehaveAppExt::add_behave_trigger`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BehaveTriggerInfo {
+    /// The type name without its module path, eg `MoveTo`.
+    pub name: String,
+    /// The full type name, eg `my_game::ai::MoveTo`.
+    pub type_name: &'static str,
+    /// The `TypeId` of `T`.
+    pub type_id: TypeId,
+}
+
+/// The trigger types registered with [`BehaveAppExt::add_behave_trigger`], or
+/// [`BehaveAppExt::add_behave_condition`], in registration order.
+///
+/// Useful for looking up trigger types by name when loading tree definitions, listing the
+/// available nodes in tooling, and finding triggers in a tree that nothing handles.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct BehaveTriggerRegistry {
+    triggers: Vec<BehaveTriggerInfo>,
+}
+
+impl BehaveTriggerRegistry {
+    /// Records `T`, if it isn't already registered.
+    pub fn register<T: 'static>(&mut self) {
+        if self.contains::<T>() {
+            return;
+        }
+        let type_name = std::any::type_name::<T>();
+        self.triggers.push(BehaveTriggerInfo {
+            name: short_type_name(type_name).to_string(),
+            type_name,
+            type_id: TypeId::of::<T>(),
+        });
+    }
+    /// Is `T` registered?
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.triggers.iter().any(|t| t.type_id == TypeId::of::<T>())
+    }
+    /// Looks up a trigger type by its short or full type name, eg `MoveTo`.
+    pub fn get(&self, name: &str) -> Option<&BehaveTriggerInfo> {
+        self.triggers
+            .iter()
+            .find(|t| t.name == name || t.type_name == name)
+    }
+    /// The registered trigger types.
+    pub fn iter(&self) -> impl Iterator<Item = &BehaveTriggerInfo> {
+        self.triggers.iter()
+    }
+    /// The `Display` names of any `Behave::trigger` nodes in `tree` whose types aren't
+    /// registered, eg `Trigger(MoveTo)`. Closures and systems don't need registering.
+    pub fn unregistered(&self, tree: &Tree<Behave>) -> Vec<String> {
+        tree.nodes()
+            .filter_map(|node| match node.value().unlabelled() {
+                Behave::TriggerReq(trigger) => trigger
+                    .trigger_type_id()
+                    .filter(|id| !self.triggers.iter().any(|t| t.type_id == *id))
+                    .map(|_| trigger.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn short_type_name(type_name: &str) -> &str {
+    // only strip the path from the outer type, eg `a::Wrapper<b::Inner>` is `Wrapper<b::Inner>`
+    let outer = type_name.split('<').next().unwrap_or(type_name);
+    let start = outer.rfind("::").map_or(0, |i| i + 2);
+    &type_name[start..]
+}
@@ -0,0 +1,168 @@
+//! Graphviz DOT and Mermaid renderings of tree definitions and running trees.
+use crate::{BehaveNode, BehaveNodeStatus, prelude::*};
+use std::fmt::Write;
+
+/// Renders a tree as a Graphviz DOT or Mermaid flowchart diagram.
+///
+/// Implemented for `Tree<Behave>` definitions, and for [`BehaveTree`] components, where each
+/// node is coloured by its current status:
+///
+/// * green: succeeded
+/// * red: failed
+/// * yellow: running, waiting on a timer, or awaiting a trigger
+/// * blue: about to be reset, to run again
+///
+/// ```
+/// # use bevy_behave::prelude::*;
+/// let tree = behave! {
+///     Behave::Sequence => {
+///         Behave::Wait(1.0),
+///         Behave::AlwaysSucceed,
+///     }
+/// };
+/// let dot = tree.to_dot();
+/// assert!(dot.contains("n0 -> n1;"));
+/// ```
+pub trait BehaveRender {
+    /// Renders a Graphviz DOT digraph, eg for `dot -Tsvg tree.dot > tree.svg`.
+    fn to_dot(&self) -> String;
+    /// Renders a Mermaid flowchart, which GitHub renders inside a ```` ```mermaid ```` block.
+    fn to_mermaid(&self) -> String;
+}
+
+impl BehaveRender for Tree<Behave> {
+    fn to_dot(&self) -> String {
+        to_dot(&diagram_nodes(self, |n| (n.value().to_string(), None)))
+    }
+    fn to_mermaid(&self) -> String {
+        to_mermaid(&diagram_nodes(self, |n| (n.value().to_string(), None)))
+    }
+}
+
+impl BehaveRender for BehaveTree {
+    fn to_dot(&self) -> String {
+        to_dot(&diagram_nodes(&self.tree, |n| node_label(self, n)))
+    }
+    fn to_mermaid(&self) -> String {
+        to_mermaid(&diagram_nodes(&self.tree, |n| node_label(self, n)))
+    }
+}
+
+fn node_label(bt: &BehaveTree, node: NodeRef<BehaveNode>) -> (String, Option<BehaveNodeStatus>) {
+    struct Kind<'a>(&'a BehaveNode);
+    impl std::fmt::Display for Kind<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.0.fmt_kind(f)
+        }
+    }
+    let kind = Kind(node.value());
+    let label = match bt.label(node.id()) {
+        Some(label) => format!("{kind} \"{label}\""),
+        None => kind.to_string(),
+    };
+    (label, *node.value().status())
+}
+
+/// A node flattened for rendering, numbered in depth-first order.
+struct DiagramNode {
+    label: String,
+    status: Option<BehaveNodeStatus>,
+    parent: Option<usize>,
+}
+
+fn diagram_nodes<T>(
+    tree: &Tree<T>,
+    label: impl Fn(NodeRef<T>) -> (String, Option<BehaveNodeStatus>),
+) -> Vec<DiagramNode> {
+    fn walk<T>(
+        node: NodeRef<T>,
+        parent: Option<usize>,
+        label: &impl Fn(NodeRef<T>) -> (String, Option<BehaveNodeStatus>),
+        out: &mut Vec<DiagramNode>,
+    ) {
+        let (text, status) = label(node);
+        let index = out.len();
+        out.push(DiagramNode {
+            label: text,
+            status,
+            parent,
+        });
+        for child in node.children() {
+            walk(child, Some(index), label, out);
+        }
+    }
+    let mut out = Vec::new();
+    walk(tree.root(), None, &label, &mut out);
+    out
+}
+
+/// (class name, fill colour) for a status.
+fn status_style(status: BehaveNodeStatus) -> (&'static str, &'static str) {
+    match status {
+        BehaveNodeStatus::Success => ("success", "#b7e4c7"),
+        BehaveNodeStatus::Failure => ("failure", "#f4a3a3"),
+        BehaveNodeStatus::Running
+        | BehaveNodeStatus::RunningTimer
+        | BehaveNodeStatus::AwaitingTrigger => ("running", "#ffe08a"),
+        BehaveNodeStatus::PendingReset => ("reset", "#a9d6f5"),
+    }
+}
+
+const STATUS_CLASSES: [BehaveNodeStatus; 4] = [
+    BehaveNodeStatus::Success,
+    BehaveNodeStatus::Failure,
+    BehaveNodeStatus::Running,
+    BehaveNodeStatus::PendingReset,
+];
+
+fn to_dot(nodes: &[DiagramNode]) -> String {
+    let mut s = String::from("digraph BehaveTree {\n");
+    s.push_str("    node [shape=box, style=\"rounded,filled\", fillcolor=\"#ffffff\"];\n");
+    for (i, node) in nodes.iter().enumerate() {
+        let label = node.label.replace('\\', "\\\\").replace('"', "\\\"");
+        match node.status {
+            Some(status) => {
+                let (_, colour) = status_style(status);
+                let _ = writeln!(s, "    n{i} [label=\"{label}\", fillcolor=\"{colour}\"];");
+            }
+            None => {
+                let _ = writeln!(s, "    n{i} [label=\"{label}\"];");
+            }
+        }
+    }
+    for (i, node) in nodes.iter().enumerate() {
+        if let Some(parent) = node.parent {
+            let _ = writeln!(s, "    n{parent} -> n{i};");
+        }
+    }
+    s.push_str("}\n");
+    s
+}
+
+fn to_mermaid(nodes: &[DiagramNode]) -> String {
+    let mut s = String::from("flowchart TD\n");
+    for (i, node) in nodes.iter().enumerate() {
+        let label = node.label.replace('"', "#quot;");
+        match node.status {
+            Some(status) => {
+                let (class, _) = status_style(status);
+                let _ = writeln!(s, "    n{i}[\"{label}\"]:::{class}");
+            }
+            None => {
+                let _ = writeln!(s, "    n{i}[\"{label}\"]");
+            }
+        }
+    }
+    for (i, node) in nodes.iter().enumerate() {
+        if let Some(parent) = node.parent {
+            let _ = writeln!(s, "    n{parent} --> n{i}");
+        }
+    }
+    if nodes.iter().any(|n| n.status.is_some()) {
+        for status in STATUS_CLASSES {
+            let (class, colour) = status_style(status);
+            let _ = writeln!(s, "    classDef {class} fill:{colour}");
+        }
+    }
+    s
+}
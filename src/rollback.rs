@@ -0,0 +1,242 @@
+//! Support for rollback networking: a simulated tick clock, and cheap snapshots of tree state.
+use crate::{
+    BehaveNode, BehaveNodeStatus, EntityTaskStatus, TaskRun, TriggerTaskStatus,
+    action::ActionTaskStatus, prelude::*,
+};
+use bevy::prelude::*;
+
+/// The clock used when ticking trees and timing `Behave::Wait` nodes and [`BehaveTimeout`]s.
+///
+/// Normally this mirrors `Time::elapsed_secs`. In rollback mode (see [`BehavePlugin::with_rollback`])
+/// it is a simulated clock, advancing a fixed amount every time the `BehaveSet` runs.
+/// Save a copy alongside your tree snapshots, and write it back when rolling back, so that
+/// resimulated ticks see identical times (and so identical [`BehaveCtx`] values).
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource)]
+pub struct BehaveClock {
+    tick: u64,
+    elapsed_secs: f32,
+    secs_per_tick: Option<f32>,
+}
+
+impl BehaveClock {
+    pub(crate) fn new(secs_per_tick: Option<f32>) -> Self {
+        Self {
+            tick: 0,
+            elapsed_secs: 0.0,
+            secs_per_tick,
+        }
+    }
+    /// How many times the `BehaveSet` has run.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+    /// The elapsed seconds as seen by the behaviour trees.
+    pub fn elapsed_secs(&self) -> f32 {
+        self.elapsed_secs
+    }
+    /// True in rollback mode, where the clock is simulated rather than following `Time`.
+    pub fn is_simulated(&self) -> bool {
+        self.secs_per_tick.is_some()
+    }
+    /// Sets the simulated clock to the given tick, eg when rolling back to a confirmed frame.
+    /// Has no effect on elapsed time unless in rollback mode.
+    pub fn set_tick(&mut self, tick: u64) {
+        self.tick = tick;
+        if let Some(secs_per_tick) = self.secs_per_tick {
+            self.elapsed_secs = Self::simulated_secs(tick, secs_per_tick);
+        }
+    }
+    // computed from the tick count each time, rather than accumulated, so it's deterministic.
+    fn simulated_secs(tick: u64, secs_per_tick: f32) -> f32 {
+        (tick as f64 * secs_per_tick as f64) as f32
+    }
+}
+
+impl Default for BehaveClock {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+pub(crate) fn tick_behave_clock(mut clock: ResMut<BehaveClock>, time: Res<Time>) {
+    let tick = clock.tick + 1;
+    match clock.secs_per_tick {
+        Some(_) => clock.set_tick(tick),
+        None => {
+            clock.tick = tick;
+            clock.elapsed_secs = time.elapsed_secs();
+        }
+    }
+}
+
+/// A copy of the runtime state of every node in a [`BehaveTree`], without the tree definition.
+///
+/// Created with [`BehaveTree::snapshot`], and applied to the same tree with [`BehaveTree::restore`].
+/// Task entities spawned by `Behave::spawn` nodes are referenced by `Entity`, so your rollback
+/// solution must restore those entities too.
+#[derive(Clone, Debug)]
+pub struct BehaveTreeSnapshot {
+    nodes: Vec<BehaveNodeState>,
+    last_tick: Option<BehaveNodeStatus>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct BehaveNodeState {
+    status: Option<BehaveNodeStatus>,
+    task: TaskState,
+}
+
+#[derive(Clone, Debug)]
+enum TaskState {
+    None,
+    Wait(Option<f32>),
+    Entity(EntityTaskStatus, TaskRun),
+    Trigger(TriggerTaskStatus, TaskRun),
+    Action(ActionTaskStatus, TaskRun),
+}
+
+impl BehaveNode {
+    pub(crate) fn state(&self) -> BehaveNodeState {
+        let task = match self {
+            BehaveNode::Wait { start_time, .. } => TaskState::Wait(*start_time),
+            BehaveNode::DynamicEntity {
+                task_status, run, ..
+            } => TaskState::Entity(task_status.clone(), run.clone()),
+            BehaveNode::TriggerReq {
+                task_status, run, ..
+            } => TaskState::Trigger(task_status.clone(), run.clone()),
+            BehaveNode::Action {
+                task_status, run, ..
+            } => TaskState::Action(task_status.clone(), run.clone()),
+            _ => TaskState::None,
+        };
+        BehaveNodeState {
+            status: *self.status(),
+            task,
+        }
+    }
+    pub(crate) fn set_state(&mut self, state: &BehaveNodeState) {
+        *self.status_mut() = state.status;
+        match (self, &state.task) {
+            (BehaveNode::Wait { start_time, .. }, TaskState::Wait(t)) => *start_time = *t,
+            (
+                BehaveNode::DynamicEntity {
+                    task_status, run, ..
+                },
+                TaskState::Entity(t, r),
+            ) => {
+                *task_status = t.clone();
+                *run = r.clone();
+            }
+            (
+                BehaveNode::TriggerReq {
+                    task_status, run, ..
+                },
+                TaskState::Trigger(t, r),
+            ) => {
+                *task_status = t.clone();
+                *run = r.clone();
+            }
+            (
+                BehaveNode::Action {
+                    task_status, run, ..
+                },
+                TaskState::Action(t, r),
+            ) => {
+                *task_status = t.clone();
+                *run = r.clone();
+            }
+            (_, TaskState::None) => {}
+            (node, _) => panic!("Snapshot state doesn't match node {node}"),
+        }
+    }
+    /// True if a status report has arrived for this task node, but not been ticked yet.
+    fn has_pending_result(&self) -> bool {
+        let complete = matches!(
+            self,
+            BehaveNode::DynamicEntity {
+                task_status: EntityTaskStatus::Complete(_),
+                ..
+            } | BehaveNode::TriggerReq {
+                task_status: TriggerTaskStatus::Complete(_),
+                ..
+            }
+        );
+        complete
+            && !matches!(
+                self.status(),
+                Some(BehaveNodeStatus::Success | BehaveNodeStatus::Failure)
+            )
+    }
+}
+
+impl BehaveTree {
+    /// Captures the runtime state of the tree, for restoring later with [`BehaveTree::restore`].
+    pub fn snapshot(&self) -> BehaveTreeSnapshot {
+        BehaveTreeSnapshot {
+            nodes: self.tree.values().map(BehaveNode::state).collect(),
+            last_tick: self.last_tick,
+        }
+    }
+
+    /// Restores the runtime state captured by [`BehaveTree::snapshot`].
+    ///
+    /// The `BehaveFinished` and internal marker components on the tree entity are brought in
+    /// line with the restored state before the next tick, when running in rollback mode.
+    ///
+    /// # Panics
+    /// The snapshot must have been taken from this tree, or a clone of it.
+    pub fn restore(&mut self, snapshot: &BehaveTreeSnapshot) {
+        assert_eq!(
+            self.tree.values().count(),
+            snapshot.nodes.len(),
+            "Snapshot was taken from a different tree"
+        );
+        for (node, state) in self.tree.values_mut().zip(snapshot.nodes.iter()) {
+            node.set_state(state);
+        }
+        self.last_tick = snapshot.last_tick;
+        self.restored = true;
+    }
+}
+
+/// After a restore, the marker components on the tree entity may no longer match the tree state,
+/// so we re-derive them from the result of the last tick.
+pub(crate) fn sync_restored_markers(
+    mut q: Query<(Entity, &mut BehaveTree)>,
+    clock: Res<BehaveClock>,
+    mut commands: Commands,
+) {
+    if !clock.is_simulated() {
+        return;
+    }
+    for (bt_entity, mut bt) in q.iter_mut() {
+        if !bt.restored {
+            continue;
+        }
+        bt.restored = false;
+        let mut e = commands.entity(bt_entity);
+        match bt.last_tick {
+            Some(BehaveNodeStatus::Success) => {
+                e.insert(BehaveFinished(true)).remove::<BehaveFailure>();
+            }
+            Some(BehaveNodeStatus::Failure) => {
+                e.insert(BehaveFinished(false));
+                if let Some(failure) = bt.failure() {
+                    e.insert(failure);
+                }
+            }
+            _ => {
+                e.remove::<(BehaveFinished, BehaveFailure)>();
+            }
+        }
+        let awaiting = bt.last_tick == Some(BehaveNodeStatus::AwaitingTrigger)
+            && !bt.tree.values().any(BehaveNode::has_pending_result);
+        if awaiting {
+            e.insert(BehaveAwaitingTrigger);
+        } else {
+            e.remove::<BehaveAwaitingTrigger>();
+        }
+    }
+}
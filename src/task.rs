@@ -0,0 +1,188 @@
+//! Task components and trigger conditions that report their result from a method.
+use crate::{
+    plugin::{BehaveSettings, tick_timeout_components},
+    prelude::*,
+    rollback::tick_behave_clock,
+};
+use bevy::ecs::{
+    component::Mutable,
+    system::{IntoObserverSystem, StaticSystemParam, SystemParam, SystemParamItem},
+};
+use bevy::prelude::*;
+
+/// A task component that is polled each frame while its task entity is running, reporting its
+/// result once it has one. Register with [`BehaveAppExt::add_behave_task`].
+///
+/// Saves writing a system that queries the component and triggers `ctx.success()` or
+/// `ctx.failure()`. Tasks of paused trees aren't polled.
+///
+/// With the `derive` feature, `#[derive(BehaveTask)]` implements this by calling an `update`
+/// method on the component, or implement it by hand:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_behave::prelude::*;
+/// # use bevy::ecs::system::SystemParamItem;
+/// #[derive(Component, Clone)]
+/// struct Sleep { secs: f32 }
+///
+/// impl BehaveTask for Sleep {
+///     type Param = Res<'static, Time>;
+///     fn poll_task(&mut self, _ctx: &BehaveCtx, time: &mut Res<Time>) -> Option<bool> {
+///         self.secs -= time.delta_secs();
+///         (self.secs <= 0.0).then_some(true)
+///     }
+/// }
+///
+/// App::new().add_plugins(BehavePlugin::default()).add_behave_task::<Sleep>();
+/// let tree = behave! { Behave::spawn_named("Sleep", Sleep { secs: 1.0 }) };
+/// ```
+pub trait BehaveTask: Component<Mutability = Mutable> + Clone {
+    /// A system param passed to [`BehaveTask::poll_task`], eg `Res<'static, Time>`, or `()`.
+    /// It must not access `Self` mutably, since the task components are already queried.
+    type Param: SystemParam + 'static;
+    /// Returns `None` while the task is still running, or `Some(success)` to report its result.
+    fn poll_task(
+        &mut self,
+        ctx: &BehaveCtx,
+        param: &mut SystemParamItem<Self::Param>,
+    ) -> Option<bool>;
+}
+
+/// A `Behave::trigger` type that checks a condition when triggered, reporting the result.
+/// Register with [`BehaveAppExt::add_behave_condition`].
+///
+/// Saves writing an observer for `BehaveTrigger<T>` that triggers `ctx.success()` or
+/// `ctx.failure()`. With the `derive` feature, `#[derive(BehaveCondition)]` implements this by
+/// calling a `check` method on the type, or implement it by hand:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_behave::prelude::*;
+/// # use bevy::ecs::system::SystemParamItem;
+/// # #[derive(Component)]
+/// # struct Health(f32);
+/// #[derive(Clone)]
+/// struct HealthBelow(f32);
+///
+/// impl BehaveCondition for HealthBelow {
+///     type Param = Query<'static, 'static, &'static Health>;
+///     fn check_condition(
+///         &self,
+///         ctx: &BehaveCtx,
+///         q: &mut SystemParamItem<Self::Param>,
+///     ) -> Option<bool> {
+///         Some(q.get(ctx.target_entity()).is_ok_and(|health| health.0 < self.0))
+///     }
+/// }
+///
+/// App::new().add_plugins(BehavePlugin::default()).add_behave_condition::<HealthBelow>();
+/// let tree = behave! { Behave::trigger(HealthBelow(10.0)) };
+/// ```
+pub trait BehaveCondition: Clone + Send + Sync + 'static {
+    /// A system param passed to [`BehaveCondition::check_condition`], or `()`.
+    type Param: SystemParam + 'static;
+    /// Returns `Some(success)` to report the result, or `None` to leave the trigger waiting for
+    /// a result reported some other way, eg later by a system holding on to the ctx.
+    fn check_condition(
+        &self,
+        ctx: &BehaveCtx,
+        param: &mut SystemParamItem<Self::Param>,
+    ) -> Option<bool>;
+}
+
+fn report(ctx: &BehaveCtx, success: bool) -> BehaveStatusReport {
+    if success {
+        ctx.success()
+    } else {
+        ctx.failure()
+    }
+}
+
+pub(crate) fn poll_behave_tasks<T: BehaveTask>(
+    mut q: Query<(&mut T, &BehaveCtx)>,
+    q_paused: Query<(), With<BehavePaused>>,
+    mut param: StaticSystemParam<T::Param>,
+    mut commands: Commands,
+) {
+    for (mut task, ctx) in q.iter_mut() {
+        if q_paused.contains(ctx.behave_entity()) {
+            continue;
+        }
+        if let Some(success) = task.poll_task(ctx, &mut param) {
+            commands.trigger(report(ctx, success));
+        }
+    }
+}
+
+pub(crate) fn check_behave_condition<T: BehaveCondition>(
+    trigger: On<BehaveTrigger<T>>,
+    mut param: StaticSystemParam<T::Param>,
+    mut commands: Commands,
+) {
+    let ctx = trigger.ctx();
+    if let Some(success) = trigger.inner().check_condition(ctx, &mut param) {
+        commands.trigger(report(ctx, success));
+    }
+}
+
+/// Registers [`BehaveTask`] and [`BehaveCondition`] types, and `BehaveTrigger<T>` handlers,
+/// with the app.
+pub trait BehaveAppExt {
+    /// Polls `T` task components each frame, in the `BehaveSet` before trees are ticked.
+    /// The `BehavePlugin` must be added first.
+    fn add_behave_task<T: BehaveTask>(&mut self) -> &mut Self;
+    /// Adds an observer that checks `T` conditions when their `BehaveTrigger<T>` fires.
+    /// Also records `T` in the [`BehaveTriggerRegistry`].
+    fn add_behave_condition<T: BehaveCondition>(&mut self) -> &mut Self;
+    /// Adds `handler` as an observer of `BehaveTrigger<T>`, and records `T` in the
+    /// [`BehaveTriggerRegistry`].
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_behave::prelude::*;
+    /// #[derive(Clone)]
+    /// struct Jump;
+    ///
+    /// fn on_jump(trigger: On<BehaveTrigger<Jump>>, mut commands: Commands) {
+    ///     commands.trigger(trigger.ctx().success());
+    /// }
+    ///
+    /// let mut app = App::new();
+    /// app.add_behave_trigger::<Jump, _>(on_jump);
+    /// assert!(app.world().resource::<BehaveTriggerRegistry>().get("Jump").is_some());
+    /// ```
+    fn add_behave_trigger<T: Clone + Send + Sync + 'static, M>(
+        &mut self,
+        handler: impl IntoObserverSystem<BehaveTrigger<T>, (), M>,
+    ) -> &mut Self;
+}
+
+impl BehaveAppExt for App {
+    fn add_behave_task<T: BehaveTask>(&mut self) -> &mut Self {
+        let schedule = self
+            .world()
+            .get_resource::<BehaveSettings>()
+            .and_then(|settings| settings.schedule)
+            .expect("add the BehavePlugin before calling add_behave_task");
+        self.add_systems(
+            schedule,
+            poll_behave_tasks::<T>
+                .after(tick_behave_clock)
+                .before(tick_timeout_components)
+                .in_set(BehaveSet),
+        )
+    }
+
+    fn add_behave_condition<T: BehaveCondition>(&mut self) -> &mut Self {
+        self.add_behave_trigger::<T, _>(check_behave_condition::<T>)
+    }
+
+    fn add_behave_trigger<T: Clone + Send + Sync + 'static, M>(
+        &mut self,
+        handler: impl IntoObserverSystem<BehaveTrigger<T>, (), M>,
+    ) -> &mut Self {
+        self.init_resource::<BehaveTriggerRegistry>();
+        self.world_mut()
+            .resource_mut::<BehaveTriggerRegistry>()
+            .register::<T>();
+        self.add_observer(handler)
+    }
+}
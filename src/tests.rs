@@ -21,6 +21,47 @@ fn test_empty_control_flow() {
     assert_tree("Fallback", tree);
 }
 
+/// Invalid trees report the path to each offending node.
+#[test]
+fn test_try_new_errors() {
+    let tree = behave! {
+        Behave::Sequence => {
+            Behave::Wait(1.0),
+            Behave::Invert => {
+                Behave::AlwaysFail,
+                Behave::AlwaysFail,
+            },
+            Behave::While => {
+                Behave::Invert,
+            }
+        }
+    };
+    assert!(!BehaveTree::verify(&tree));
+    let errors = BehaveTree::verify_errors(&tree);
+    assert_eq!(
+        errors,
+        vec![
+            BehaveTreeError::InvalidChildren {
+                path: "root/Invert[1]".to_string(),
+                node: "Invert".to_string(),
+                children: 2,
+                permitted: 1..=1,
+            },
+            BehaveTreeError::InvalidChildren {
+                path: "root/While[2]/Invert".to_string(),
+                node: "Invert".to_string(),
+                children: 0,
+                permitted: 1..=1,
+            },
+        ]
+    );
+    assert_eq!(
+        errors[1].to_string(),
+        "root/While[2]/Invert: Invert has 0 children, permitted: exactly 1"
+    );
+    assert_eq!(BehaveTree::try_new(tree).err(), Some(errors[0].clone()));
+}
+
 /// Tests using the @ [] syntax for including a list of task nodes,
 /// eg Behave::spawn_named or Wait etc – nothing that has children.
 #[test]
@@ -330,7 +371,7 @@ fn test_behave_interrupt() {
     }
 
     fn check_interrupt(
-        trigger: Trigger<BehaveTrigger<CheckInterrupt>>,
+        trigger: On<BehaveTrigger<CheckInterrupt>>,
         test_state: Res<TestState>,
         mut commands: Commands,
     ) {
@@ -342,11 +383,11 @@ fn test_behave_interrupt() {
     }
 
     fn on_task_finished(
-        trigger: Trigger<OnAdd, BehaveFinished>,
+        trigger: On<Add, BehaveFinished>,
         query: Query<&BehaveFinished>,
-        mut exit: EventWriter<AppExit>,
+        mut exit: MessageWriter<AppExit>,
     ) {
-        let finished = query.get(trigger.target()).unwrap();
+        let finished = query.get(trigger.event().entity).unwrap();
         let result = finished.0;
         assert!(result, "long task was not interrupted.");
         exit.write(AppExit::Success);
@@ -401,12 +442,12 @@ fn test_behave_interrupt_inverted() {
         interrupt: bool,
     }
 
-    fn check_interrupt(trigger: Trigger<BehaveTrigger<CheckInterrupt>>, mut commands: Commands) {
+    fn check_interrupt(trigger: On<BehaveTrigger<CheckInterrupt>>, mut commands: Commands) {
         commands.trigger(trigger.ctx().failure());
     }
 
     fn check_second_interrupt(
-        trigger: Trigger<BehaveTrigger<CheckSecondInterrupt>>,
+        trigger: On<BehaveTrigger<CheckSecondInterrupt>>,
         test_state: Res<TestState>,
         mut commands: Commands,
     ) {
@@ -418,11 +459,11 @@ fn test_behave_interrupt_inverted() {
     }
 
     fn on_task_finished(
-        trigger: Trigger<OnAdd, BehaveFinished>,
+        trigger: On<Add, BehaveFinished>,
         query: Query<&BehaveFinished>,
-        mut exit: EventWriter<AppExit>,
+        mut exit: MessageWriter<AppExit>,
     ) {
-        let finished = query.get(trigger.target()).unwrap();
+        let finished = query.get(trigger.event().entity).unwrap();
         let result = finished.0;
         assert!(result, "long task was not interrupted.");
         exit.write(AppExit::Success);
@@ -458,6 +499,1785 @@ fn test_behave_interrupt_inverted() {
     app.run();
 }
 
+/// In rollback mode, restoring a snapshot (and the clock) and resimulating must produce
+/// identical BehaveCtx values in observers.
+#[test]
+fn test_rollback_resimulation() {
+    use crate::prelude::*;
+    use bevy::prelude::*;
+
+    #[derive(Clone)]
+    struct Record;
+
+    #[derive(Resource, Default)]
+    struct Seen(Vec<BehaveCtx>);
+
+    fn on_record(t: On<BehaveTrigger<Record>>, mut seen: ResMut<Seen>, mut commands: Commands) {
+        seen.0.push(*t.ctx());
+        commands.trigger(t.ctx().success());
+    }
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        BehavePlugin::new(Update).with_rollback(1.0 / 60.0),
+    ));
+    app.init_resource::<Seen>();
+    app.add_observer(on_record);
+
+    let tree = behave! {
+        Behave::Sequence => {
+            Behave::trigger(Record),
+            Behave::Wait(0.05),
+            Behave::trigger(Record),
+        }
+    };
+    let bt_entity = app.world_mut().spawn(BehaveTree::new(tree)).id();
+    app.update();
+
+    let snapshot = app.world().get::<BehaveTree>(bt_entity).unwrap().snapshot();
+    let clock = *app.world().resource::<BehaveClock>();
+    let seen_at_snapshot = app.world().resource::<Seen>().0.len();
+
+    // every node's status after each update, and when the Wait node started.
+    let frame_state = |app: &App| {
+        let bt = app.world().get::<BehaveTree>(bt_entity).unwrap();
+        let statuses = bt
+            .tree
+            .nodes()
+            .map(|n| *n.value().status())
+            .collect::<Vec<_>>();
+        let wait_start = bt.tree.nodes().find_map(|n| match n.value() {
+            crate::BehaveNode::Wait { start_time, .. } => Some(*start_time),
+            _ => None,
+        });
+        (statuses, wait_start.flatten())
+    };
+
+    let resimulate = |app: &mut App| {
+        let mut frames = Vec::new();
+        for _ in 0..10 {
+            app.update();
+            frames.push(frame_state(app));
+        }
+        assert!(app.world().get::<BehaveFinished>(bt_entity).is_some());
+        let seen = app
+            .world_mut()
+            .resource_mut::<Seen>()
+            .0
+            .split_off(seen_at_snapshot);
+        (frames, seen)
+    };
+
+    let first_run = resimulate(&mut app);
+    assert_eq!(first_run.1.len(), 1);
+    // the wait started on the tick after the snapshot, and the second trigger fires once
+    // 0.05s of simulated time has passed since then.
+    let wait_start = first_run.0[0].1.expect("wait started after the snapshot");
+    assert!(first_run.1[0].elapsed_secs() - wait_start >= 0.05);
+
+    // roll back to the snapshot, and run the same ticks again.
+    app.world_mut()
+        .get_mut::<BehaveTree>(bt_entity)
+        .unwrap()
+        .restore(&snapshot);
+    *app.world_mut().resource_mut::<BehaveClock>() = clock;
+
+    let second_run = resimulate(&mut app);
+    assert_eq!(first_run, second_run);
+}
+
+/// Every tree accepted by `try_new` ticks without a BehaveError, eg `Forever` with two children
+/// is rejected up front rather than failing on its first tick.
+#[test]
+fn test_try_new_agrees_with_tick() {
+    use bevy::prelude::*;
+
+    let kinds = [
+        Behave::Sequence,
+        Behave::Fallback,
+        Behave::Forever,
+        Behave::While,
+        Behave::IfThen,
+        Behave::Invert,
+    ];
+    for kind in kinds {
+        for child_count in 0..=3 {
+            let mut tree = Tree::new(kind.clone());
+            for _ in 0..child_count {
+                tree.root_mut().append(Behave::AlwaysSucceed);
+            }
+            let Ok(bt) = BehaveTree::try_new(tree) else {
+                assert!(!kind.permitted_children().contains(&child_count));
+                continue;
+            };
+            let mut app = App::new();
+            app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+            let bt_entity = app.world_mut().spawn(bt).id();
+            app.update();
+            app.update();
+            assert!(
+                app.world().get::<BehaveError>(bt_entity).is_none(),
+                "{kind} with {child_count} children errored when ticked"
+            );
+        }
+    }
+    let tree = behave! {
+        Behave::Forever => {
+            Behave::AlwaysSucceed,
+            Behave::AlwaysSucceed,
+        }
+    };
+    assert_eq!(
+        BehaveTree::try_new(tree).err().unwrap().to_string(),
+        "root: Forever has 2 children, permitted: exactly 1"
+    );
+}
+
+/// A tree that becomes malformed after verification stops with a BehaveError, rather than panicking.
+#[test]
+fn test_malformed_tree_error() {
+    use crate::BehaveNode;
+    use bevy::prelude::*;
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+
+    let mut bt = BehaveTree::new(behave! {
+        Behave::Sequence => {
+            Behave::AlwaysSucceed,
+            Behave::Invert => {
+                Behave::AlwaysFail,
+            },
+        }
+    });
+    // give the Invert a second child, bypassing verification
+    let invert_id = bt.tree.root().last_child().unwrap().id();
+    bt.tree
+        .get_mut(invert_id)
+        .unwrap()
+        .append(BehaveNode::new(Behave::AlwaysFail));
+    let bt_entity = app.world_mut().spawn(bt).id();
+    app.update();
+    app.update();
+
+    let finished = app.world().get::<BehaveFinished>(bt_entity).unwrap();
+    assert!(!finished.0);
+    let error = app.world().get::<BehaveError>(bt_entity).unwrap();
+    assert_eq!(
+        error.to_string(),
+        "root/Invert[1]: Invert has 2 children, permitted: exactly 1"
+    );
+}
+
+/// A started task entity whose node status was changed by hand, eg by restoring a snapshot,
+/// keeps waiting for the task entity to report instead of panicking.
+#[test]
+fn test_started_task_with_status_changed() {
+    use bevy::prelude::*;
+
+    #[derive(Component, Clone)]
+    struct LongTask;
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+    let bt_entity = app
+        .world_mut()
+        .spawn(BehaveTree::new(behave! {
+            Behave::spawn_named("Long", LongTask)
+        }))
+        .id();
+    app.update();
+    app.update();
+
+    let mut bt = app.world_mut().get_mut::<BehaveTree>(bt_entity).unwrap();
+    *bt.tree.root_mut().value().status_mut() = None;
+    app.world_mut()
+        .entity_mut(bt_entity)
+        .remove::<crate::plugin::BehaveAwaitingTrigger>();
+    app.update();
+    app.update();
+    assert!(app.world().get::<BehaveFinished>(bt_entity).is_none());
+
+    let ctx = *app
+        .world_mut()
+        .query_filtered::<&BehaveCtx, With<LongTask>>()
+        .single(app.world())
+        .unwrap();
+    app.world_mut().trigger(ctx.success());
+    app.update();
+    assert!(app.world().get::<BehaveFinished>(bt_entity).unwrap().0);
+}
+
+/// Running task entities are halted and despawned when their subtree resets, or the tree is despawned.
+#[test]
+fn test_halt_running_tasks() {
+    use bevy::prelude::*;
+
+    #[derive(Component, Clone)]
+    struct LongTask;
+
+    #[derive(Resource, Default)]
+    struct Halted(Vec<Entity>);
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+    app.init_resource::<Halted>();
+    app.add_observer(|t: On<BehaveHalted>, mut halted: ResMut<Halted>| {
+        halted.0.push(t.event().entity);
+    });
+
+    let tree = behave! {
+        Behave::Forever => {
+            Behave::spawn_named("Long task", LongTask),
+        }
+    };
+    let bt_entity = app.world_mut().spawn(BehaveTree::new(tree)).id();
+    app.update();
+    app.update();
+
+    let mut q_tasks = app.world_mut().query_filtered::<Entity, With<LongTask>>();
+    let first_task = q_tasks.single(app.world()).unwrap();
+
+    // reset the tree while the task is still running
+    let mut bt = app.world_mut().get_mut::<BehaveTree>(bt_entity).unwrap();
+    *bt.tree.root_mut().value().status_mut() = Some(crate::BehaveNodeStatus::PendingReset);
+    app.world_mut()
+        .entity_mut(bt_entity)
+        .remove::<crate::plugin::BehaveAwaitingTrigger>();
+    app.update();
+
+    assert_eq!(app.world().resource::<Halted>().0, vec![first_task]);
+    assert!(app.world().get_entity(first_task).is_err());
+    let second_task = q_tasks.single(app.world()).unwrap();
+    assert_ne!(first_task, second_task);
+
+    app.world_mut().entity_mut(bt_entity).despawn();
+    app.update();
+    assert_eq!(
+        app.world().resource::<Halted>().0,
+        vec![first_task, second_task]
+    );
+    assert!(q_tasks.iter(app.world()).next().is_none());
+}
+
+/// Pausing stops time passing for Wait nodes, abort halts running tasks and finishes the tree,
+/// and restart runs the tree from the start again.
+#[test]
+fn test_control_commands() {
+    use bevy::prelude::*;
+
+    #[derive(Component, Clone)]
+    struct LongTask;
+
+    #[derive(Resource, Default)]
+    struct Halted(usize);
+
+    #[derive(Resource, Default)]
+    struct Finished(Vec<bool>);
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update).with_rollback(0.1)));
+    app.init_resource::<Halted>();
+    app.init_resource::<Finished>();
+    app.add_observer(|_: On<BehaveHalted>, mut halted: ResMut<Halted>| halted.0 += 1);
+    app.add_observer(
+        |t: On<BehaveTreeFinished>, mut finished: ResMut<Finished>| {
+            finished.0.push(t.success);
+        },
+    );
+
+    let tree = behave! {
+        Behave::Sequence => {
+            Behave::Wait(1.0),
+            Behave::spawn_named("Long task", LongTask),
+        }
+    };
+    let bt_entity = app.world_mut().spawn(BehaveTree::new(tree)).id();
+    let run = |app: &mut App, updates: usize| {
+        for _ in 0..updates {
+            app.update();
+        }
+        let mut q_tasks = app.world_mut().query_filtered::<(), With<LongTask>>();
+        q_tasks.iter(app.world()).count()
+    };
+    let command = |app: &mut App, f: fn(&mut EntityCommands)| {
+        f(&mut app.world_mut().commands().entity(bt_entity));
+        app.world_mut().flush();
+    };
+
+    assert_eq!(run(&mut app, 2), 0);
+    command(&mut app, |e| {
+        e.behave_pause();
+    });
+    assert_eq!(run(&mut app, 20), 0, "no time passes while paused");
+    command(&mut app, |e| {
+        e.behave_resume();
+    });
+    assert_eq!(run(&mut app, 5), 0, "wait resumes where it left off");
+    assert_eq!(run(&mut app, 10), 1);
+
+    command(&mut app, |e| {
+        e.behave_abort();
+    });
+    assert_eq!(app.world().resource::<Halted>().0, 1);
+    assert!(!app.world().get::<BehaveFinished>(bt_entity).unwrap().0);
+    assert_eq!(app.world().resource::<Finished>().0, [false]);
+    assert_eq!(run(&mut app, 1), 0);
+
+    command(&mut app, |e| {
+        e.behave_restart();
+    });
+    assert!(app.world().get::<BehaveFinished>(bt_entity).is_none());
+    assert_eq!(run(&mut app, 5), 0);
+    assert_eq!(run(&mut app, 10), 1);
+}
+
+/// BehaveOnFinish policies, and the BehaveTreeFinished event.
+#[test]
+fn test_on_finish_policies() {
+    use bevy::prelude::*;
+
+    #[derive(Clone)]
+    struct Report(bool);
+
+    #[derive(Resource, Default)]
+    struct Finished(Vec<(Entity, bool)>);
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+    app.init_resource::<Finished>();
+    app.add_observer(|t: On<BehaveTrigger<Report>>, mut commands: Commands| {
+        let ctx = t.ctx();
+        commands.trigger(if t.inner().0 {
+            ctx.success()
+        } else {
+            ctx.failure()
+        });
+    });
+    app.add_observer(
+        |t: On<BehaveTreeFinished>, mut finished: ResMut<Finished>| {
+            finished.0.push((t.event().entity, t.event().success));
+        },
+    );
+
+    let spawn = |app: &mut App, success: bool, on_finish: BehaveOnFinish| {
+        let tree = behave! { Behave::trigger(Report(success)) };
+        let target = app.world_mut().spawn_empty().id();
+        let bt = app
+            .world_mut()
+            .spawn((BehaveTree::new(tree), on_finish, ChildOf(target)))
+            .id();
+        (target, bt)
+    };
+    let (keep_target, keep) = spawn(&mut app, true, BehaveOnFinish::Keep);
+    let (_, restart) = spawn(&mut app, false, BehaveOnFinish::Restart);
+    let (despawn_tree_target, despawn_tree) = spawn(&mut app, true, BehaveOnFinish::DespawnTree);
+    let (despawn_target, despawn_target_tree) =
+        spawn(&mut app, false, BehaveOnFinish::DespawnTarget);
+    for _ in 0..6 {
+        app.update();
+    }
+
+    let finished = &app.world().resource::<Finished>().0;
+    let count = |e| finished.iter().filter(|(f, _)| *f == e).count();
+    assert_eq!(count(keep), 1);
+    assert!(finished.contains(&(keep, true)));
+    assert!(count(restart) > 1, "restarted trees finish repeatedly");
+    assert!(finished.contains(&(restart, false)));
+    assert_eq!(count(despawn_tree), 1);
+    assert_eq!(count(despawn_target_tree), 1);
+
+    let world = app.world();
+    assert!(world.get::<BehaveFinished>(keep).unwrap().0);
+    assert!(world.get_entity(keep_target).is_ok());
+    assert!(world.get::<BehaveFinished>(restart).is_none());
+    assert!(world.get_entity(despawn_tree).is_err());
+    assert!(world.get_entity(despawn_tree_target).is_ok());
+    assert!(world.get_entity(despawn_target).is_err());
+    assert!(world.get_entity(despawn_target_tree).is_err());
+}
+
+/// With synchronous ticking, a restarted tree that finishes within one tick runs again next
+/// frame, rather than looping until the sanity limit.
+#[test]
+fn test_restart_sync() {
+    use bevy::prelude::*;
+
+    #[derive(Resource, Default)]
+    struct Finished(usize);
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update).with_synchronous()));
+    app.init_resource::<Finished>();
+    app.add_observer(
+        |_t: On<BehaveTreeFinished>, mut finished: ResMut<Finished>| {
+            finished.0 += 1;
+        },
+    );
+    app.world_mut().spawn((
+        BehaveTree::new(behave! { Behave::AlwaysSucceed }),
+        BehaveOnFinish::Restart,
+    ));
+    for frame in 1..=3 {
+        app.update();
+        assert_eq!(app.world().resource::<Finished>().0, frame);
+    }
+}
+
+/// A late status report from an earlier run of a looping node is ignored.
+#[test]
+fn test_stale_status_report_ignored() {
+    use bevy::prelude::*;
+
+    #[derive(Clone)]
+    struct Hold;
+
+    #[derive(Resource, Default)]
+    struct Held(Vec<BehaveCtx>);
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+    app.init_resource::<Held>();
+    app.add_observer(|t: On<BehaveTrigger<Hold>>, mut held: ResMut<Held>| {
+        held.0.push(*t.ctx());
+    });
+
+    let tree = behave! {
+        Behave::Forever => {
+            Behave::trigger(Hold),
+        }
+    };
+    app.world_mut().spawn(BehaveTree::new(tree));
+    let report = |app: &mut App, ctx: BehaveCtx| {
+        app.world_mut().trigger(ctx.success());
+        for _ in 0..3 {
+            app.update();
+        }
+        app.world().resource::<Held>().0.clone()
+    };
+    app.update();
+    app.update();
+
+    let first = app.world().resource::<Held>().0[0];
+    let held = report(&mut app, first);
+    assert_eq!(held.len(), 2, "the loop runs the trigger again");
+    assert_eq!(held[1].generation(), first.generation() + 1);
+
+    let held = report(&mut app, first);
+    assert_eq!(
+        held.len(),
+        2,
+        "a repeated report for the first run is ignored"
+    );
+
+    let held = report(&mut app, held[1]);
+    assert_eq!(held.len(), 3);
+}
+
+/// A late status report that arrives after the tree restarts, but before the node runs again,
+/// is ignored rather than completing the restarted node.
+#[test]
+fn test_stale_status_report_after_restart() {
+    use bevy::prelude::*;
+
+    #[derive(Clone)]
+    struct Hold;
+
+    #[derive(Resource, Default)]
+    struct Held(Vec<BehaveCtx>);
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+    app.init_resource::<Held>();
+    app.add_observer(|t: On<BehaveTrigger<Hold>>, mut held: ResMut<Held>| {
+        held.0.push(*t.ctx());
+    });
+    let bt_entity = app
+        .world_mut()
+        .spawn(BehaveTree::new(behave! { Behave::trigger(Hold) }))
+        .id();
+    app.update();
+    app.update();
+    let first = app.world().resource::<Held>().0[0];
+
+    app.world_mut()
+        .commands()
+        .entity(bt_entity)
+        .behave_restart();
+    app.world_mut().flush();
+    app.world_mut().trigger(first.success());
+    for _ in 0..3 {
+        app.update();
+    }
+    assert!(app.world().get::<BehaveFinished>(bt_entity).is_none());
+    let held = &app.world().resource::<Held>().0;
+    assert_eq!(held.len(), 2, "the restarted node runs again");
+    assert_ne!(held[1].generation(), first.generation());
+}
+
+/// Despawning a task entity before it reports a result reports failure (or the configured result).
+#[test]
+fn test_despawned_task_reports_result() {
+    use bevy::prelude::*;
+
+    #[derive(Component, Clone)]
+    struct Doomed;
+
+    for (plugin, expected) in [
+        (BehavePlugin::new(Update), false),
+        (
+            BehavePlugin::new(Update).with_despawned_task_result(true),
+            true,
+        ),
+    ] {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, plugin));
+        let tree = behave! {
+            Behave::Sequence => {
+                Behave::spawn_named("Doomed", Doomed),
+                Behave::AlwaysSucceed,
+            }
+        };
+        let bt_entity = app.world_mut().spawn(BehaveTree::new(tree)).id();
+        app.update();
+        app.update();
+
+        let mut q_task = app.world_mut().query_filtered::<Entity, With<Doomed>>();
+        let task = q_task.single(app.world()).unwrap();
+        app.world_mut().entity_mut(task).despawn();
+        app.update();
+        app.update();
+
+        let finished = app.world().get::<BehaveFinished>(bt_entity).unwrap();
+        assert_eq!(finished.0, expected);
+    }
+}
+
+/// With trigger checks enabled, trees using triggers without observers stop with an error,
+/// either when spawned, or when the trigger fires.
+#[test]
+fn test_unobserved_trigger_checks() {
+    use bevy::prelude::*;
+
+    #[derive(Clone)]
+    struct Forgotten;
+    #[derive(Clone)]
+    struct Observed;
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        BehavePlugin::new(Update)
+            .with_trigger_checks()
+            .with_rollback(0.1),
+    ));
+    let observer = app
+        .world_mut()
+        .add_observer(|t: On<BehaveTrigger<Observed>>, mut commands: Commands| {
+            commands.trigger(t.ctx().success());
+        })
+        .id();
+
+    let forgotten = app
+        .world_mut()
+        .spawn(BehaveTree::new(behave! {
+            Behave::Sequence => {
+                Behave::trigger(Observed),
+                Behave::trigger(Forgotten),
+            }
+        }))
+        .id();
+    let observed = app
+        .world_mut()
+        .spawn(BehaveTree::new(behave! {
+            Behave::Sequence => {
+                Behave::Wait(0.1),
+                Behave::trigger(Observed),
+            }
+        }))
+        .id();
+    app.update();
+
+    let error = app.world().get::<BehaveError>(forgotten).unwrap();
+    assert_eq!(
+        error.0,
+        BehaveTreeError::UnobservedTrigger {
+            path: "root/Trigger(bevy_behave::tests::test_unobserved_trigger_checks::Forgotten)[1]"
+                .to_string(),
+            trigger: std::any::type_name::<Forgotten>().to_string(),
+        }
+    );
+    assert!(app.world().get::<BehaveError>(observed).is_none());
+
+    // the observer goes away before the trigger fires
+    app.world_mut().despawn(observer);
+    for _ in 0..3 {
+        app.update();
+    }
+    let error = app.world().get::<BehaveError>(observed).unwrap();
+    assert!(matches!(error.0, BehaveTreeError::UnobservedTrigger { .. }));
+    assert!(!app.world().get::<BehaveFinished>(observed).unwrap().0);
+}
+
+/// Overdue task nodes are flagged by the watchdog, warning or failing them as configured.
+#[test]
+fn test_watchdog() {
+    use bevy::prelude::*;
+
+    #[derive(Clone)]
+    struct Ignored;
+    #[derive(Component, Clone)]
+    struct Stuck;
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        BehavePlugin::new(Update)
+            .with_rollback(0.1)
+            .with_watchdog(BehaveWatchdog::warn_after(0.5)),
+    ));
+    app.add_observer(|_: On<BehaveTrigger<Ignored>>| {});
+
+    // only warns, using the plugin setting
+    let warned = app
+        .world_mut()
+        .spawn(BehaveTree::new(behave! {
+            Behave::trigger(Ignored)
+        }))
+        .id();
+    // the component overrides the plugin setting
+    let failed_trigger = app
+        .world_mut()
+        .spawn((
+            BehaveTree::new(behave! {
+                Behave::Fallback => {
+                    Behave::trigger(Ignored),
+                    Behave::spawn_named("Stuck", Stuck),
+                }
+            }),
+            BehaveWatchdog::fail_after(0.5),
+        ))
+        .id();
+    for _ in 0..8 {
+        app.update();
+    }
+    assert!(app.world().get::<BehaveFinished>(warned).is_none());
+    assert_eq!(
+        app.world().get::<BehaveTree>(warned).unwrap().overdue.len(),
+        1
+    );
+    // the trigger failed, so the fallback moved on to the spawned task
+    assert!(app.world().get::<BehaveFinished>(failed_trigger).is_none());
+    let mut q = app.world_mut().query_filtered::<Entity, With<Stuck>>();
+    let stuck = q.single(app.world()).unwrap();
+
+    for _ in 0..6 {
+        app.update();
+    }
+    assert!(!app.world().get::<BehaveFinished>(failed_trigger).unwrap().0);
+    assert!(app.world().get_entity(stuck).is_err());
+}
+
+/// Failure reasons reported by tasks reach BehaveFailure, traced through control flow nodes.
+#[test]
+fn test_failure_reasons() {
+    use bevy::prelude::*;
+
+    #[derive(Clone)]
+    struct Blocked;
+    #[derive(Debug, PartialEq)]
+    struct Obstacle(u32);
+
+    #[derive(Resource, Default)]
+    struct Failures(Vec<BehaveFailure>);
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+    app.init_resource::<Failures>();
+    app.add_observer(|t: On<BehaveTrigger<Blocked>>, mut commands: Commands| {
+        let reason = BehaveFailureReason::new("path blocked").with_payload(Obstacle(7));
+        commands.trigger(t.ctx().failure_because(reason));
+    });
+    app.add_observer(
+        |t: On<BehaveTreeFinished>, mut failures: ResMut<Failures>| {
+            failures.0.extend(t.event().failure.clone());
+        },
+    );
+
+    let blocked = app
+        .world_mut()
+        .spawn(BehaveTree::new(behave! {
+            Behave::Sequence => {
+                Behave::AlwaysSucceed,
+                Behave::Fallback => {
+                    Behave::AlwaysFail,
+                    Behave::trigger(Blocked),
+                }
+            }
+        }))
+        .id();
+    let inverted = app
+        .world_mut()
+        .spawn(BehaveTree::new(behave! {
+            Behave::Fallback => {
+                Behave::trigger(Blocked),
+                Behave::Invert => {
+                    Behave::AlwaysSucceed,
+                }
+            }
+        }))
+        .id();
+    for _ in 0..4 {
+        app.update();
+    }
+
+    let failure = app.world().get::<BehaveFailure>(blocked).unwrap();
+    assert!(failure.node_name.starts_with("Trigger("));
+    assert!(failure.path.starts_with("root/Fallback[1]/Trigger("));
+    let reason = failure.reason.as_ref().unwrap();
+    assert_eq!(reason.message(), "path blocked");
+    assert_eq!(reason.payload::<Obstacle>(), Some(&Obstacle(7)));
+    assert!(failure.to_string().ends_with(" failed: path blocked"));
+
+    // the trigger's failure was handled by the fallback, so the invert is to blame
+    let failure = app.world().get::<BehaveFailure>(inverted).unwrap();
+    assert_eq!(failure.path, "root/Invert[1]");
+    assert!(failure.reason.is_none());
+    assert_eq!(failure.to_string(), "root/Invert[1]: Invert failed");
+
+    assert_eq!(app.world().resource::<Failures>().0.len(), 2);
+}
+
+/// Closure nodes report their result without a trigger type or observer.
+#[test]
+fn test_run_and_condition_closures() {
+    use bevy::prelude::*;
+
+    #[derive(Resource, Default)]
+    struct Counter(u32);
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        BehavePlugin::new(Update).with_trigger_checks(),
+    ));
+    app.init_resource::<Counter>();
+
+    let tree = behave! {
+        Behave::Sequence => {
+            Behave::run(|_, world| {
+                world.resource_mut::<Counter>().0 += 1;
+                true
+            }),
+            Behave::IfThen => {
+                Behave::condition(|_, world| world.resource::<Counter>().0 > 5),
+                Behave::AlwaysSucceed,
+                Behave::run(|_, world| {
+                    world.resource_mut::<Counter>().0 += 10;
+                    false
+                }),
+            }
+        }
+    };
+    assert_tree(
+        "Sequence
+            ├── Run
+            └── IfThen
+                ├── Condition
+                ├── AlwaysSucceed
+                └── Run",
+        tree.clone(),
+    );
+    let bt = app.world_mut().spawn(BehaveTree::new(tree)).id();
+    for _ in 0..8 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<Counter>().0, 11);
+    assert!(!app.world().get::<BehaveFinished>(bt).unwrap().0);
+    assert!(app.world().get::<BehaveError>(bt).is_none());
+}
+
+/// Conditional systems run with their own system params, and are registered once per system.
+#[test]
+fn test_conditional_system() {
+    use bevy::prelude::*;
+
+    #[derive(Component)]
+    struct Health(f32);
+    #[derive(Resource, Default)]
+    struct Runs(u32);
+
+    fn is_hurt(In(ctx): In<BehaveCtx>, q: Query<&Health>, mut runs: ResMut<Runs>) -> bool {
+        runs.0 += 1;
+        q.get(ctx.target_entity()).is_ok_and(|h| h.0 < 20.0)
+    }
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+    app.init_resource::<Runs>();
+
+    let make_tree = || {
+        behave! {
+            Behave::Sequence => {
+                Behave::Wait(0.0),
+                Behave::conditional(is_hurt),
+            }
+        }
+    };
+    let tree = make_tree();
+    assert!(tree.to_string().contains("Conditional("));
+    assert!(tree.to_string().contains("is_hurt)"));
+    let entities_before = app.world().entities().len();
+
+    let hurt = app.world_mut().spawn(Health(10.0)).id();
+    let healthy = app.world_mut().spawn(Health(100.0)).id();
+    let hurt_bt = app
+        .world_mut()
+        .spawn((BehaveTree::new(tree.clone()), ChildOf(hurt)))
+        .id();
+    let healthy_bt = app
+        .world_mut()
+        .spawn((BehaveTree::new(tree), ChildOf(healthy)))
+        .id();
+    // a separately built tree using the same system
+    let other_bt = app
+        .world_mut()
+        .spawn((BehaveTree::new(make_tree()), ChildOf(hurt)))
+        .id();
+    for _ in 0..8 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<Runs>().0, 3);
+    assert!(app.world().get::<BehaveFinished>(hurt_bt).unwrap().0);
+    assert!(!app.world().get::<BehaveFinished>(healthy_bt).unwrap().0);
+    assert!(app.world().get::<BehaveFinished>(other_bt).unwrap().0);
+    // the three trees and two targets, plus one entity for the registered system
+    assert_eq!(app.world().entities().len(), entities_before + 6);
+}
+
+#[test]
+fn test_action_async() {
+    run_actions(false);
+}
+
+#[test]
+fn test_action_sync() {
+    run_actions(true);
+}
+
+/// Actions are ticked inline once per frame until they finish, and halted if the tree stops.
+fn run_actions(sync: bool) {
+    use bevy::prelude::*;
+
+    #[derive(Resource, Default, Debug, PartialEq)]
+    struct Calls {
+        starts: u32,
+        ticks: u32,
+        halts: u32,
+    }
+
+    #[derive(Clone)]
+    struct CountDown(u32);
+
+    impl BehaveAction for CountDown {
+        fn on_start(&mut self, _ctx: &BehaveCtx, world: &mut World) {
+            world.resource_mut::<Calls>().starts += 1;
+        }
+        fn on_tick(&mut self, _ctx: &BehaveCtx, world: &mut World) -> BehaveActionStatus {
+            world.resource_mut::<Calls>().ticks += 1;
+            if self.0 == 0 {
+                return BehaveActionStatus::Success;
+            }
+            self.0 -= 1;
+            BehaveActionStatus::Running
+        }
+        fn on_halt(&mut self, _ctx: &BehaveCtx, world: &mut World) {
+            world.resource_mut::<Calls>().halts += 1;
+        }
+    }
+
+    let mut app = App::new();
+    let plugin = BehavePlugin::new(Update);
+    let plugin = if sync {
+        plugin.with_synchronous()
+    } else {
+        plugin
+    };
+    app.add_plugins((MinimalPlugins, plugin));
+    app.init_resource::<Calls>();
+
+    let tree = behave! {
+        Behave::Sequence => {
+            Behave::action(CountDown(2)),
+            Behave::action(CountDown(100)),
+        }
+    };
+    assert_tree(
+        "Sequence
+            ├── Action(CountDown)
+            └── Action(CountDown)",
+        tree.clone(),
+    );
+    let bt = app.world_mut().spawn(BehaveTree::new(tree)).id();
+    let entities = app.world().entities().len();
+    for _ in 0..5 {
+        app.update();
+    }
+    // the first action ticked 3 times over 3 frames, and the second has started
+    let calls = app.world().resource::<Calls>();
+    assert_eq!((calls.starts, calls.halts), (2, 0));
+    assert!(calls.ticks >= 4 && calls.ticks <= 6, "{calls:?}");
+    assert_eq!(app.world().entities().len(), entities);
+
+    app.world_mut().commands().entity(bt).behave_abort();
+    app.update();
+    assert_eq!(app.world().resource::<Calls>().halts, 1);
+    assert!(!app.world().get::<BehaveFinished>(bt).unwrap().0);
+}
+
+/// An action that fails with a reason passes it on to the tree's `BehaveFailure`.
+#[test]
+fn test_action_failure_reason() {
+    use bevy::prelude::*;
+
+    #[derive(Clone)]
+    struct NoPath;
+
+    impl BehaveAction for NoPath {
+        fn on_tick(&mut self, _ctx: &BehaveCtx, _world: &mut World) -> BehaveActionStatus {
+            BehaveActionStatus::Failure(Some("no path".into()))
+        }
+    }
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+    let bt = app
+        .world_mut()
+        .spawn(BehaveTree::new(behave! { Behave::action(NoPath) }))
+        .id();
+    for _ in 0..3 {
+        app.update();
+    }
+    assert!(!app.world().get::<BehaveFinished>(bt).unwrap().0);
+    let failure = app.world().get::<BehaveFailure>(bt).unwrap();
+    assert_eq!(failure.path, "root");
+    assert_eq!(failure.reason.as_ref().unwrap().message(), "no path");
+}
+
+/// Task components are polled until they return a result, and conditions report theirs.
+#[test]
+fn test_behave_task_and_condition() {
+    use bevy::ecs::system::SystemParamItem;
+    use bevy::prelude::*;
+
+    #[derive(Component)]
+    struct Health(f32);
+    #[derive(Resource, Default)]
+    struct Polls(u32);
+
+    #[derive(Component, Clone)]
+    struct CountDown(u32);
+
+    impl BehaveTask for CountDown {
+        type Param = ResMut<'static, Polls>;
+        fn poll_task(&mut self, _ctx: &BehaveCtx, polls: &mut ResMut<Polls>) -> Option<bool> {
+            polls.0 += 1;
+            if self.0 == 0 {
+                return Some(true);
+            }
+            self.0 -= 1;
+            None
+        }
+    }
+
+    #[derive(Clone)]
+    struct IsHurt;
+
+    impl BehaveCondition for IsHurt {
+        type Param = Query<'static, 'static, &'static Health>;
+        fn check_condition(
+            &self,
+            ctx: &BehaveCtx,
+            q: &mut SystemParamItem<Self::Param>,
+        ) -> Option<bool> {
+            Some(q.get(ctx.target_entity()).is_ok_and(|h| h.0 < 20.0))
+        }
+    }
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+    app.init_resource::<Polls>();
+    app.add_behave_task::<CountDown>()
+        .add_behave_condition::<IsHurt>();
+
+    let tree = behave! {
+        Behave::Sequence => {
+            Behave::spawn_named("CountDown", CountDown(2)),
+            Behave::trigger(IsHurt),
+        }
+    };
+    let hurt = app.world_mut().spawn(Health(10.0)).id();
+    let healthy = app.world_mut().spawn(Health(100.0)).id();
+    let hurt_bt = app
+        .world_mut()
+        .spawn((BehaveTree::new(tree.clone()), ChildOf(hurt)))
+        .id();
+    let healthy_bt = app
+        .world_mut()
+        .spawn((BehaveTree::new(tree), ChildOf(healthy)))
+        .id();
+    for _ in 0..10 {
+        app.update();
+    }
+    // each tree's task polled three times, returning a result on the third
+    assert_eq!(app.world().resource::<Polls>().0, 6);
+    assert!(app.world().get::<BehaveFinished>(hurt_bt).unwrap().0);
+    assert!(!app.world().get::<BehaveFinished>(healthy_bt).unwrap().0);
+}
+
+/// The derives call the named method, passing the system param if one is given.
+#[cfg(feature = "derive")]
+#[test]
+fn test_behave_derives() {
+    use bevy::prelude::*;
+
+    #[derive(Component, Clone, BehaveTask)]
+    struct Succeed;
+
+    impl Succeed {
+        fn update(&mut self, _ctx: &BehaveCtx) -> Option<bool> {
+            Some(true)
+        }
+    }
+
+    #[derive(Clone, BehaveCondition)]
+    #[behave(method = is_enabled, param = Res<'static, Enabled>)]
+    struct CheckEnabled;
+
+    #[derive(Resource)]
+    struct Enabled(bool);
+
+    impl CheckEnabled {
+        fn is_enabled(&self, _ctx: &BehaveCtx, enabled: &mut Res<Enabled>) -> Option<bool> {
+            Some(enabled.0)
+        }
+    }
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+    app.insert_resource(Enabled(false));
+    app.add_behave_task::<Succeed>()
+        .add_behave_condition::<CheckEnabled>();
+
+    let tree = behave! {
+        Behave::Sequence => {
+            Behave::spawn_named("Succeed", Succeed),
+            Behave::trigger(CheckEnabled),
+        }
+    };
+    let target = app.world_mut().spawn_empty().id();
+    let bt = app
+        .world_mut()
+        .spawn((BehaveTree::new(tree), ChildOf(target)))
+        .id();
+    for _ in 0..6 {
+        app.update();
+    }
+    assert!(!app.world().get::<BehaveFinished>(bt).unwrap().0);
+}
+
+/// Trigger handlers and conditions are recorded in the registry, so unhandled triggers can be found.
+#[test]
+fn test_trigger_registry() {
+    use bevy::ecs::system::SystemParamItem;
+    use bevy::prelude::*;
+
+    #[derive(Clone)]
+    struct Jump;
+    #[derive(Clone)]
+    struct Duck;
+    #[derive(Clone)]
+    struct IsSafe;
+
+    impl BehaveCondition for IsSafe {
+        type Param = ();
+        fn check_condition(&self, _ctx: &BehaveCtx, _: &mut SystemParamItem<()>) -> Option<bool> {
+            Some(true)
+        }
+    }
+
+    fn on_jump(trigger: On<BehaveTrigger<Jump>>, mut commands: Commands) {
+        commands.trigger(trigger.ctx().success());
+    }
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+    app.add_behave_trigger::<Jump, _>(on_jump)
+        .add_behave_condition::<IsSafe>()
+        // registering twice only records the type once
+        .add_behave_condition::<IsSafe>();
+
+    let registry = app.world().resource::<BehaveTriggerRegistry>();
+    let names: Vec<_> = registry.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["Jump", "IsSafe"]);
+    assert!(registry.contains::<Jump>());
+    assert!(!registry.contains::<Duck>());
+    let jump = registry.get("Jump").unwrap();
+    assert_eq!(jump.type_name, std::any::type_name::<Jump>());
+    assert_eq!(registry.get(jump.type_name), Some(jump));
+    assert!(registry.get("Duck").is_none());
+
+    let tree = behave! {
+        Behave::Sequence => {
+            Behave::trigger(IsSafe),
+            Behave::trigger(Jump),
+            Behave::trigger(Duck),
+            Behave::condition(|_ctx, _world| true),
+        }
+    };
+    let unregistered = registry.unregistered(&tree);
+    assert_eq!(unregistered.len(), 1);
+    assert!(unregistered[0].contains("Duck"));
+
+    let bt = app.world_mut().spawn(BehaveTree::new(tree)).id();
+    for _ in 0..6 {
+        app.update();
+    }
+    // the tree waits on Duck, since nothing handles it
+    assert!(app.world().get::<BehaveFinished>(bt).is_none());
+}
+
+/// Async tasks report the result of their future, and halting the node cancels the future.
+#[test]
+fn test_async_task() {
+    use bevy::prelude::*;
+    use bevy::tasks::futures_lite::future;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
+
+    struct SetOnDrop(Arc<AtomicBool>);
+    impl Drop for SetOnDrop {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+
+    let tree = behave! {
+        Behave::Sequence => {
+            Behave::async_task(|_ctx| async { true }),
+            Behave::async_task(|_ctx| async { false }),
+        }
+    };
+    let bt = app.world_mut().spawn(BehaveTree::new(tree)).id();
+    for _ in 0..50 {
+        app.update();
+        if app.world().get::<BehaveFinished>(bt).is_some() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(2));
+    }
+    let failure = app.world().get::<BehaveFailure>(bt).unwrap();
+    assert_eq!(failure.path, "root/Spawn(AsyncTask)[1]");
+
+    let dropped = Arc::new(AtomicBool::new(false));
+    let guard_flag = dropped.clone();
+    let tree = behave! {
+        Behave::async_task(move |_ctx| {
+            let guard = SetOnDrop(guard_flag.clone());
+            async move {
+                let _guard = guard;
+                future::pending::<bool>().await
+            }
+        })
+    };
+    let bt = app.world_mut().spawn(BehaveTree::new(tree)).id();
+    for _ in 0..4 {
+        app.update();
+    }
+    assert!(!dropped.load(Ordering::SeqCst));
+    app.world_mut().commands().entity(bt).behave_abort();
+    app.update();
+    assert!(dropped.load(Ordering::SeqCst));
+}
+
+/// Labelled nodes can be found by label, and their labels show in paths, output and contexts.
+#[test]
+fn test_labels() {
+    use bevy::prelude::*;
+
+    #[derive(Clone)]
+    struct Attack;
+    #[derive(Resource, Default)]
+    struct Seen(Vec<Option<&'static str>>);
+
+    fn on_attack(
+        trigger: On<BehaveTrigger<Attack>>,
+        mut seen: ResMut<Seen>,
+        mut commands: Commands,
+    ) {
+        seen.0.push(trigger.ctx().label());
+        commands.trigger(trigger.ctx().failure());
+    }
+
+    let tree = behave! {
+        Behave::Sequence.named("combat") => {
+            Behave::trigger(Attack).named("swing").named("strike"),
+            Behave::trigger(Attack),
+        }
+    };
+    assert_eq!(tree.root().value().to_string(), "Sequence \"combat\"");
+    assert_eq!(tree.root().value().label(), Some("combat"));
+    let children = tree.root().children().map(|c| c.value().to_string());
+    assert_eq!(
+        children.collect::<Vec<_>>(),
+        [
+            "Trigger(bevy_behave::tests::test_labels::Attack) \"strike\"",
+            "Trigger(bevy_behave::tests::test_labels::Attack)",
+        ]
+    );
+    assert!(BehaveTree::lint(&tree).is_empty());
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+    app.init_resource::<Seen>();
+    app.add_observer(on_attack);
+    let bt = BehaveTree::new(tree);
+    let strike = bt.find("strike").unwrap();
+    assert_eq!(bt.label(strike), Some("strike"));
+    assert_eq!(bt.find("combat"), Some(bt.tree.root().id()));
+    assert!(bt.find("swing").is_none());
+    assert!(bt.to_string().contains("[strike]"));
+
+    let bt_entity = app.world_mut().spawn(bt).id();
+    for _ in 0..4 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<Seen>().0, [Some("strike")]);
+    let failure = app.world().get::<BehaveFailure>(bt_entity).unwrap();
+    assert_eq!(failure.path, "root/strike[0]");
+    assert_eq!(failure.node, strike);
+    assert!(failure.node_name.ends_with("\"strike\""));
+}
+
+/// The introspection api reports node statuses, and the chain of running nodes.
+#[test]
+fn test_introspection() {
+    use bevy::prelude::*;
+
+    #[derive(Clone)]
+    struct Hold;
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+    // never responds, so the trigger stays running
+    app.add_observer(|_trigger: On<BehaveTrigger<Hold>>| {});
+
+    let tree = behave! {
+        Behave::Sequence => {
+            Behave::AlwaysSucceed,
+            Behave::Fallback.named("fallback") => {
+                Behave::trigger(Hold),
+            },
+            Behave::Wait(1.0),
+        }
+    };
+    let bt_entity = app.world_mut().spawn(BehaveTree::new(tree)).id();
+    let bt = app.world().get::<BehaveTree>(bt_entity).unwrap();
+    assert!(bt.active_path().is_empty());
+    assert!(bt.nodes().all(|n| n.status.is_none()));
+
+    for _ in 0..3 {
+        app.update();
+    }
+    let bt = app.world().get::<BehaveTree>(bt_entity).unwrap();
+    let active = bt.active_path();
+    let paths: Vec<_> = active.iter().map(|n| n.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "root",
+            "root/fallback[1]",
+            "root/fallback[1]/Trigger(bevy_behave::tests::test_introspection::Hold)"
+        ]
+    );
+    assert_eq!(active[1].label, Some("fallback"));
+    assert_eq!(active[1].kind, "Fallback");
+    assert_eq!(active[2].depth, 2);
+    assert!(
+        active
+            .iter()
+            .all(|n| n.status == Some(BehaveStatus::Running))
+    );
+
+    let statuses: Vec<_> = bt.nodes().map(|n| (n.kind, n.status)).collect();
+    assert_eq!(
+        statuses[1],
+        ("AlwaysSucceed".into(), Some(BehaveStatus::Success))
+    );
+    assert_eq!(statuses[4], ("Wait(1s)".into(), None));
+    let root = bt.node(bt.tree.root().id()).unwrap();
+    assert_eq!(root, active[0]);
+}
+
+/// Node events are only triggered when enabled, for all or just labelled nodes.
+#[test]
+fn test_node_events() {
+    use bevy::prelude::*;
+
+    #[derive(Resource, Default)]
+    struct Events(Vec<String>);
+
+    fn run(node_events: BehaveNodeEvents) -> Vec<String> {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BehavePlugin::new(Update)));
+        app.init_resource::<Events>();
+        let tree = behave! {
+            Behave::Sequence => {
+                Behave::AlwaysSucceed.named("first"),
+                Behave::Invert.named("invert") => {
+                    Behave::Wait(0.0),
+                },
+            }
+        };
+        app.world_mut()
+            .spawn(BehaveTree::new(tree).with_node_events(node_events))
+            .observe(|t: On<BehaveNodeStarted>, mut events: ResMut<Events>| {
+                events.0.push(format!("start {}", t.label.unwrap_or("-")));
+            })
+            .observe(|t: On<BehaveNodeFinished>, mut events: ResMut<Events>| {
+                let label = t.label.unwrap_or("-");
+                events.0.push(format!("finish {label} {:?}", t.status));
+            });
+        for _ in 0..4 {
+            app.update();
+        }
+        std::mem::take(&mut app.world_mut().resource_mut::<Events>().0)
+    }
+
+    assert!(run(BehaveNodeEvents::Off).is_empty());
+    assert_eq!(
+        run(BehaveNodeEvents::Labelled),
+        [
+            "start first",
+            "finish first Success",
+            "start invert",
+            "finish invert Failure",
+        ]
+    );
+    assert_eq!(
+        run(BehaveNodeEvents::All),
+        [
+            "start -",
+            "start first",
+            "finish first Success",
+            "start invert",
+            "start -",
+            "finish - Success",
+            "finish invert Failure",
+            "finish - Failure",
+        ]
+    );
+}
+
+/// BehaveTrigger observers on an entity only see triggers from trees targeting that entity.
+#[test]
+fn test_entity_targeted_triggers() {
+    use bevy::prelude::*;
+
+    #[derive(Clone)]
+    struct Speak;
+
+    #[derive(Resource, Default)]
+    struct Spoken(Vec<String>);
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        BehavePlugin::new(Update).with_trigger_checks(),
+    ));
+    app.init_resource::<Spoken>();
+    let tree = behave! { Behave::trigger(Speak) };
+    let speaker = |sound: &'static str| {
+        move |t: On<BehaveTrigger<Speak>>, mut spoken: ResMut<Spoken>, mut commands: Commands| {
+            spoken.0.push(sound.to_string());
+            commands.trigger(t.ctx().success());
+        }
+    };
+    // one observer per npc, each only sees triggers from its own tree
+    let dog = app.world_mut().spawn_empty().observe(speaker("woof")).id();
+    let cat = app.world_mut().spawn_empty().observe(speaker("meow")).id();
+    app.world_mut()
+        .spawn((BehaveTree::new(tree.clone()), ChildOf(dog)));
+    app.world_mut()
+        .spawn((BehaveTree::new(tree.clone()), ChildOf(cat)));
+    // or target the tree entity itself
+    app.world_mut()
+        .spawn((
+            BehaveTree::new(tree),
+            BehaveTriggerTarget::Tree,
+            ChildOf(cat),
+        ))
+        .observe(speaker("tree"));
+    app.update();
+    app.update();
+
+    let mut spoken = app.world().resource::<Spoken>().0.clone();
+    spoken.sort();
+    assert_eq!(spoken, ["meow", "tree", "woof"]);
+}
+
+#[cfg(feature = "xml")]
+#[derive(Clone)]
+struct IsBatteryOk;
+
+#[cfg(feature = "xml")]
+fn xml_importer() -> BehaveXmlImporter {
+    #[derive(bevy::prelude::Component, Clone)]
+    struct MoveTo;
+
+    BehaveXmlImporter::new()
+        .with_trigger("IsBatteryOk", IsBatteryOk)
+        .with_leaf_fn("MoveTo", |ports| {
+            let goal = ports.get("goal").ok_or("missing goal port")?;
+            Ok(Behave::spawn_named(format!("MoveTo {goal}"), MoveTo))
+        })
+}
+
+#[cfg(feature = "xml")]
+#[test]
+fn test_xml_import() {
+    let xml = r#"
+        <root BTCPP_format="4" main_tree_to_execute="Main">
+            <BehaviorTree ID="Main">
+                <Fallback>
+                    <Sequence>
+                        <Inverter>
+                            <Condition ID="IsBatteryOk"/>
+                        </Inverter>
+                        <SubTree ID="Recharge"/>
+                    </Sequence>
+                    <RetryUntilSuccessful num_attempts="2">
+                        <MoveTo goal="{target}"/>
+                    </RetryUntilSuccessful>
+                </Fallback>
+            </BehaviorTree>
+            <BehaviorTree ID="Recharge">
+                <Sleep msec="1500"/>
+            </BehaviorTree>
+            <TreeNodesModel>
+                <Action ID="MoveTo"/>
+            </TreeNodesModel>
+        </root>
+    "#;
+    let tree = xml_importer().import(xml).unwrap();
+    assert!(BehaveTree::verify(&tree));
+    assert_eq!(
+        tree.to_string(),
+        format!(
+            "Fallback
+├── Sequence
+│   ├── Invert
+│   │   └── Trigger({})
+│   └── Wait(1.5s)
+└── Fallback
+    ├── Spawn(MoveTo {{target}})
+    └── Spawn(MoveTo {{target}})
+",
+            std::any::type_name::<IsBatteryOk>()
+        )
+    );
+}
+
+#[cfg(feature = "xml")]
+#[test]
+fn test_xml_import_errors() {
+    let wrap =
+        |body: &str| format!(r#"<root><BehaviorTree ID="Main">{body}</BehaviorTree></root>"#);
+    let err = |body: &str| xml_importer().import(&wrap(body)).unwrap_err();
+
+    assert_eq!(
+        err("<Parallel><IsBatteryOk/></Parallel>"),
+        BehaveXmlError::UnsupportedNode {
+            tag: "Parallel".into(),
+            line: 1
+        }
+    );
+    assert_eq!(
+        err("<Sequence><OpenDoor/></Sequence>"),
+        BehaveXmlError::UnknownLeaf {
+            name: "OpenDoor".into(),
+            line: 1
+        }
+    );
+    assert!(matches!(
+        err("<MoveTo/>"),
+        BehaveXmlError::InvalidLeaf { reason, .. } if reason == "missing goal port"
+    ));
+    assert!(matches!(
+        err("<Inverter><IsBatteryOk/><IsBatteryOk/></Inverter>"),
+        BehaveXmlError::InvalidChildren { children: 2, .. }
+    ));
+    assert!(matches!(
+        err(r#"<RetryUntilSuccessful num_attempts="lots"><IsBatteryOk/></RetryUntilSuccessful>"#),
+        BehaveXmlError::InvalidAttribute { value: Some(_), .. }
+    ));
+    assert_eq!(
+        err(r#"<SubTree ID="Main"/>"#),
+        BehaveXmlError::RecursiveSubTree("Main".into())
+    );
+    assert!(matches!(
+        err(r#"<RetryUntilSuccessful num_attempts="0"><IsBatteryOk/></RetryUntilSuccessful>"#),
+        BehaveXmlError::InvalidAttribute { value: Some(v), .. } if v == "0"
+    ));
+    assert!(matches!(
+        err("<IfThenElse><IsBatteryOk/></IfThenElse>"),
+        BehaveXmlError::InvalidChildren { children: 1, .. }
+    ));
+    assert!(matches!(
+        err("<WhileDoElse><IsBatteryOk/></WhileDoElse>"),
+        BehaveXmlError::InvalidChildren { children: 1, .. }
+    ));
+}
+
+#[cfg(feature = "xml")]
+#[test]
+fn test_xml_if_then_else_and_while_do_else() {
+    let wrap =
+        |body: &str| format!(r#"<root><BehaviorTree ID="Main">{body}</BehaviorTree></root>"#);
+    let import = |body: &str| {
+        let tree = xml_importer().import(&wrap(body)).unwrap();
+        assert!(BehaveTree::verify(&tree));
+        tree.to_string()
+            .replace(std::any::type_name::<IsBatteryOk>(), "IsBatteryOk")
+    };
+
+    assert_eq!(
+        import("<IfThenElse><IsBatteryOk/><Sleep msec=\"1000\"/><AlwaysFailure/></IfThenElse>"),
+        "IfThen
+├── Trigger(IsBatteryOk)
+├── Wait(1s)
+└── AlwaysFail
+"
+    );
+    assert_eq!(
+        import("<WhileDoElse><IsBatteryOk/><Sleep msec=\"1000\"/></WhileDoElse>"),
+        "IfThen
+├── Trigger(IsBatteryOk)
+└── Wait(1s)
+"
+    );
+    assert_eq!(
+        import("<WhileDoElse><IsBatteryOk/><Sleep msec=\"1000\"/><AlwaysFailure/></WhileDoElse>"),
+        "IfThen
+├── Trigger(IsBatteryOk)
+├── Wait(1s)
+└── AlwaysFail
+"
+    );
+}
+
+fn render_tree() -> Tree<Behave> {
+    behave! {
+        Behave::Fallback => {
+            Behave::AlwaysFail,
+            Behave::Invert => {
+                Behave::Wait(2.0),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_render_definition() {
+    assert_eq!(
+        render_tree().to_dot(),
+        r##"digraph BehaveTree {
+    node [shape=box, style="rounded,filled", fillcolor="#ffffff"];
+    n0 [label="Fallback"];
+    n1 [label="AlwaysFail"];
+    n2 [label="Invert"];
+    n3 [label="Wait(2s)"];
+    n0 -> n1;
+    n0 -> n2;
+    n2 -> n3;
+}
+"##
+    );
+    assert_eq!(
+        render_tree().to_mermaid(),
+        r#"flowchart TD
+    n0["Fallback"]
+    n1["AlwaysFail"]
+    n2["Invert"]
+    n3["Wait(2s)"]
+    n0 --> n1
+    n0 --> n2
+    n2 --> n3
+"#
+    );
+}
+
+#[test]
+fn test_render_status_colours() {
+    use crate::BehaveNodeStatus;
+
+    let mut bt = BehaveTree::new(render_tree());
+    let mut values = bt.tree.values_mut();
+    *values.next().unwrap().status_mut() = Some(BehaveNodeStatus::Running);
+    *values.next().unwrap().status_mut() = Some(BehaveNodeStatus::Failure);
+
+    let dot = bt.to_dot();
+    assert!(dot.contains(r##"n0 [label="FallbackFlow", fillcolor="#ffe08a"];"##));
+    assert!(dot.contains(r##"n1 [label="AlwaysFail", fillcolor="#f4a3a3"];"##));
+    assert!(dot.contains(r#"n3 [label="Wait(2)"];"#));
+
+    let mermaid = bt.to_mermaid();
+    assert!(mermaid.contains(r#"n0["FallbackFlow"]:::running"#));
+    assert!(mermaid.contains(r#"n1["AlwaysFail"]:::failure"#));
+    assert!(mermaid.contains("classDef failure fill:#f4a3a3"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_ron_definition() {
+    let def: BehaveDef = ron::from_str(
+        r#"
+        Forever([
+            Sequence([
+                Leaf("WaitUntilPlayerIsNear"),
+                Invert([ Leaf("IsTired") ]),
+                Wait(5.0),
+            ]),
+        ])
+        "#,
+    )
+    .unwrap();
+    let tree = def.to_placeholder_tree();
+    assert!(BehaveTree::verify(&tree));
+    assert_eq!(
+        tree.to_string(),
+        "Forever
+└── Sequence
+    ├── Spawn(WaitUntilPlayerIsNear)
+    ├── Invert
+    │   └── Spawn(IsTired)
+    └── Wait(5s)
+"
+    );
+
+    let unknown = def.to_tree(|name| (name == "IsTired").then_some(Behave::AlwaysFail));
+    assert_eq!(unknown.unwrap_err(), "WaitUntilPlayerIsNear");
+}
+
+#[test]
+fn test_lints() {
+    #[derive(Clone)]
+    struct IsHungry;
+
+    let tree = behave! {
+        Behave::Forever => {
+            Behave::Sequence => {
+                Behave::Fallback => {
+                    Behave::trigger(IsHungry),
+                    Behave::AlwaysSucceed,
+                    Behave::Wait(1.0),
+                    Behave::Wait(2.0),
+                },
+                Behave::Invert => {
+                    Behave::Invert => {
+                        Behave::AlwaysFail,
+                    }
+                },
+                Behave::While => {
+                    Behave::AlwaysSucceed,
+                },
+                Behave::Fallback,
+            }
+        }
+    };
+    assert!(BehaveTree::verify(&tree));
+    let kinds = BehaveTree::lint(&tree)
+        .into_iter()
+        .map(|l| (l.kind, l.path))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            (
+                BehaveLintKind::UnreachableChildren { count: 2 },
+                "root/Sequence/While[2]".to_string()
+            ),
+            (
+                BehaveLintKind::UnreachableChildren { count: 2 },
+                "root/Sequence/Fallback[0]/Wait(1s)[2]".to_string()
+            ),
+            (
+                BehaveLintKind::DoubleInvert,
+                "root/Sequence/Invert[1]/Invert".to_string()
+            ),
+            (
+                BehaveLintKind::EndlessWhile,
+                "root/Sequence/While[2]".to_string()
+            ),
+            (
+                BehaveLintKind::EmptyControlFlow,
+                "root/Sequence/Fallback[3]".to_string()
+            ),
+        ]
+    );
+
+    let busy = behave! {
+        Behave::Forever => {
+            Behave::Invert => {
+                Behave::AlwaysFail,
+            }
+        }
+    };
+    let lints = BehaveTree::lint(&busy);
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].kind, BehaveLintKind::ForeverWithoutTask);
+    assert_eq!(
+        lints[0].to_string(),
+        "root: Forever has no task beneath it, so loops without waiting"
+    );
+
+    let fine = behave! {
+        Behave::Forever => {
+            Behave::Sequence => {
+                Behave::trigger(IsHungry),
+                Behave::Invert => {
+                    Behave::AlwaysFail,
+                },
+                Behave::While => {
+                    Behave::trigger(IsHungry),
+                    Behave::Wait(1.0),
+                },
+            }
+        }
+    };
+    assert_eq!(BehaveTree::lint(&fine), vec![]);
+
+    // labelled nodes are linted as the node they label
+    let labelled = behave! {
+        Behave::Sequence => {
+            Behave::AlwaysFail.named("give up"),
+            Behave::Wait(1.0),
+        }
+    };
+    let lints = BehaveTree::lint(&labelled);
+    assert_eq!(lints.len(), 1);
+    assert_eq!(
+        lints[0].kind,
+        BehaveLintKind::UnreachableChildren { count: 1 }
+    );
+}
+
 /// asserts the tree.to_string matches the expected string, accounting for whitespace/indentation
 fn assert_tree(s: &str, tree: Tree<Behave>) {
     // strip and tidy any indent spaces in the expected output so we can easily compare
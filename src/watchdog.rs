@@ -0,0 +1,117 @@
+//! Watchdog that flags task nodes left waiting on a result for too long.
+use crate::{
+    BehaveNode, EntityTaskStatus, TriggerTaskStatus,
+    plugin::{BehaveSettings, TickCtx},
+    prelude::*,
+};
+use bevy::prelude::*;
+
+/// Flags any spawn or trigger node in a tree that has been waiting on a result for longer than
+/// `max_secs`, logging a warning with the tree. Each run of a node is only flagged once.
+///
+/// Insert on a tree entity to watch that tree, or enable for every tree with
+/// `BehavePlugin::with_watchdog`. The component takes precedence over the plugin setting.
+/// Time spent paused doesn't count towards the limit.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct BehaveWatchdog {
+    /// How long a node may wait for a result before being flagged.
+    pub max_secs: f32,
+    /// Also report failure for a flagged node, so the tree can move on.
+    pub report_failure: bool,
+}
+
+impl BehaveWatchdog {
+    /// Logs a warning for nodes waiting longer than `max_secs`.
+    pub fn warn_after(max_secs: f32) -> Self {
+        Self {
+            max_secs,
+            report_failure: false,
+        }
+    }
+    /// Logs a warning and reports failure for nodes waiting longer than `max_secs`.
+    pub fn fail_after(max_secs: f32) -> Self {
+        Self {
+            max_secs,
+            report_failure: true,
+        }
+    }
+}
+
+pub(crate) fn check_watchdogs(
+    mut q: Query<
+        (Entity, &mut BehaveTree, Option<&BehaveWatchdog>),
+        (Without<BehaveFinished>, Without<BehavePaused>),
+    >,
+    q_ctx: Query<&BehaveCtx>,
+    settings: Res<BehaveSettings>,
+    clock: Res<BehaveClock>,
+    mut commands: Commands,
+) {
+    for (bt_entity, mut bt, opt_watchdog) in q.iter_mut() {
+        let Some(watchdog) = opt_watchdog.copied().or(settings.watchdog) else {
+            continue;
+        };
+        let mut overdue = Vec::new();
+        for node in bt.tree.nodes() {
+            let waiting = match node.value() {
+                BehaveNode::DynamicEntity {
+                    task_status: EntityTaskStatus::Started(e),
+                    run,
+                    ..
+                } => Some((Some(*e), run)),
+                BehaveNode::TriggerReq {
+                    task_status: TriggerTaskStatus::Triggered,
+                    run,
+                    ..
+                } => Some((None, run)),
+                _ => None,
+            };
+            let Some((task_entity, run)) = waiting else {
+                continue;
+            };
+            let waited = clock.elapsed_secs() - run.started_secs;
+            if waited > watchdog.max_secs && !bt.overdue.contains(&(node.id(), run.generation)) {
+                overdue.push((node.id(), task_entity, run.clone(), waited));
+            }
+        }
+        // forget nodes that have since moved on to another run
+        let BehaveTree {
+            tree,
+            overdue: flagged,
+            ..
+        } = &mut *bt;
+        flagged.retain(|(id, generation)| {
+            tree.get(*id)
+                .and_then(|n| n.value().task_run())
+                .is_some_and(|run| run.generation == *generation)
+        });
+        for (node_id, task_entity, run, waited) in overdue {
+            bt.overdue.push((node_id, run.generation));
+            warn!(
+                "Node {} in tree {bt_entity} has been waiting {waited:.1}s for a result, more than the watchdog limit of {}s\n{}",
+                bt.node_name(node_id),
+                watchdog.max_secs,
+                *bt
+            );
+            if !watchdog.report_failure {
+                continue;
+            }
+            let ctx = match task_entity {
+                Some(task_entity) => match q_ctx.get(task_entity) {
+                    Ok(ctx) => *ctx,
+                    Err(_) => continue,
+                },
+                None => {
+                    let tick_ctx = TickCtx::new(bt_entity, Entity::PLACEHOLDER, run.started_secs)
+                        .with_labels(bt.labels.clone());
+                    BehaveCtx::new_for_trigger(node_id, run.generation, &tick_ctx)
+                }
+            };
+            commands.trigger(ctx.failure_because(format!(
+                "no result after {waited:.1}s, watchdog limit is {}s",
+                watchdog.max_secs
+            )));
+        }
+    }
+}
@@ -0,0 +1,467 @@
+//! Importer for [BehaviorTree.CPP](https://www.behaviortree.dev/) / Groot XML tree definitions.
+//!
+//! Enabled with the `xml` feature.
+use crate::prelude::*;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The attributes (ports) of an XML node, passed to leaf factories registered with
+/// [`BehaveXmlImporter::with_leaf_fn`]. The `ID` and `name` attributes are not included.
+pub type BehaveXmlPorts = HashMap<String, String>;
+
+type LeafFactory = Arc<dyn Fn(&BehaveXmlPorts) -> Result<Behave, String> + Send + Sync>;
+
+/// Converts BehaviorTree.CPP XML into a `Tree<Behave>`.
+///
+/// Built-in control and decorator nodes are mapped to their `Behave` equivalents:
+///
+/// | BehaviorTree.CPP                       | bevy_behave                                      |
+/// | -------------------------------------- | ------------------------------------------------ |
+/// | `Sequence`                             | `Behave::Sequence`                               |
+/// | `Fallback`                             | `Behave::Fallback`                               |
+/// | `Inverter`                             | `Behave::Invert`                                 |
+/// | `AlwaysSuccess` / `AlwaysFailure`      | `Behave::AlwaysSucceed` / `Behave::AlwaysFail`   |
+/// | `Sleep msec="..."`                     | `Behave::Wait`                                   |
+/// | `KeepRunningUntilFailure`              | `Behave::While` with a single child              |
+/// | `IfThenElse`                           | `Behave::IfThen`                                 |
+/// | `WhileDoElse`                          | `Behave::IfThen`, losing the reactivity: the condition isn't re-checked while the body runs |
+/// | `ForceSuccess` / `ForceFailure`        | `Fallback` / `Sequence` with the child and an `AlwaysSucceed` / `AlwaysFail` |
+/// | `RetryUntilSuccessful num_attempts="n"`| `Fallback` of `n` copies of the child (`-1` retries forever, `0` is rejected) |
+/// | `SubTree ID="..."`                     | the referenced `<BehaviorTree>`, inlined          |
+///
+/// Any other tag, or `<Action ID="..."/>` and `<Condition ID="..."/>`, is looked up in the
+/// leaves registered with this importer, by tag name or `ID`.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_behave::prelude::*;
+/// #[derive(Component, Clone)]
+/// struct MoveToGoal;
+/// #[derive(Clone)]
+/// struct IsBatteryOk;
+///
+/// let xml = r#"
+///     <root BTCPP_format="4" main_tree_to_execute="Main">
+///         <BehaviorTree ID="Main">
+///             <Sequence>
+///                 <IsBatteryOk/>
+///                 <MoveToGoal goal="{target}"/>
+///             </Sequence>
+///         </BehaviorTree>
+///     </root>
+/// "#;
+/// let tree = BehaveXmlImporter::new()
+///     .with_trigger("IsBatteryOk", IsBatteryOk)
+///     .with_spawn("MoveToGoal", MoveToGoal)
+///     .import(xml)
+///     .unwrap();
+/// assert!(BehaveTree::verify(&tree));
+/// ```
+#[derive(Default, Clone)]
+pub struct BehaveXmlImporter {
+    leaves: HashMap<String, LeafFactory>,
+}
+
+impl BehaveXmlImporter {
+    /// Creates an importer with no registered leaves.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps a tag name (or `ID`) to a `Behave` node, which is cloned wherever the tag appears.
+    pub fn with_leaf(self, name: impl Into<String>, behave: Behave) -> Self {
+        self.with_leaf_fn(name, move |_| Ok(behave.clone()))
+    }
+
+    /// Maps a tag name (or `ID`) to a `Behave::spawn_named` node spawning `bundle`,
+    /// using the tag name as the entity name.
+    pub fn with_spawn<T: Bundle + Clone>(self, name: impl Into<String>, bundle: T) -> Self {
+        let name = name.into();
+        let behave = Behave::spawn_named(name.clone(), bundle);
+        self.with_leaf(name, behave)
+    }
+
+    /// Maps a tag name (or `ID`) to a `Behave::trigger` node, emitting `BehaveTrigger<T>`.
+    pub fn with_trigger<T: Clone + Send + Sync + 'static>(
+        self,
+        name: impl Into<String>,
+        value: T,
+    ) -> Self {
+        self.with_leaf(name, Behave::trigger(value))
+    }
+
+    /// Maps a tag name (or `ID`) to a fn that builds the `Behave` node from the node's ports.
+    /// Return an `Err` to reject invalid ports; it's reported as [`BehaveXmlError::InvalidLeaf`].
+    pub fn with_leaf_fn(
+        mut self,
+        name: impl Into<String>,
+        f: impl Fn(&BehaveXmlPorts) -> Result<Behave, String> + Send + Sync + 'static,
+    ) -> Self {
+        self.leaves.insert(name.into(), Arc::new(f));
+        self
+    }
+
+    /// Imports the main tree of the document.
+    ///
+    /// That's the tree named by the `main_tree_to_execute` attribute of the root element,
+    /// or the only `<BehaviorTree>` if there's just one.
+    pub fn import(&self, xml: &str) -> Result<Tree<Behave>, BehaveXmlError> {
+        let doc = parse(xml)?;
+        let root = doc.root_element();
+        let id = match root.attribute("main_tree_to_execute") {
+            Some(id) => id.to_string(),
+            None => {
+                let mut trees = behavior_trees(&doc);
+                match (trees.next(), trees.next()) {
+                    (Some(tree), None) => tree.attribute("ID").unwrap_or_default().to_string(),
+                    _ => return Err(BehaveXmlError::NoMainTree),
+                }
+            }
+        };
+        self.convert_tree(&doc, &id, &mut Vec::new())
+    }
+
+    /// Imports the `<BehaviorTree>` with the given `ID`.
+    pub fn import_tree(&self, xml: &str, id: &str) -> Result<Tree<Behave>, BehaveXmlError> {
+        let doc = parse(xml)?;
+        self.convert_tree(&doc, id, &mut Vec::new())
+    }
+
+    /// Converts a `<BehaviorTree>` element, tracking the stack of subtree ids to catch recursion.
+    fn convert_tree(
+        &self,
+        doc: &roxmltree::Document,
+        id: &str,
+        stack: &mut Vec<String>,
+    ) -> Result<Tree<Behave>, BehaveXmlError> {
+        if stack.iter().any(|s| s == id) {
+            return Err(BehaveXmlError::RecursiveSubTree(id.to_string()));
+        }
+        let tree_node = behavior_trees(doc)
+            .find(|n| n.attribute("ID") == Some(id))
+            .ok_or_else(|| BehaveXmlError::TreeNotFound(id.to_string()))?;
+        let children = element_children(tree_node);
+        let [root] = children.as_slice() else {
+            return Err(BehaveXmlError::InvalidChildren {
+                tag: format!("BehaviorTree ID=\"{id}\""),
+                children: children.len(),
+                line: line(tree_node),
+            });
+        };
+        stack.push(id.to_string());
+        let tree = self.convert_node(doc, *root, stack);
+        stack.pop();
+        tree
+    }
+
+    fn convert_node(
+        &self,
+        doc: &roxmltree::Document,
+        node: roxmltree::Node,
+        stack: &mut Vec<String>,
+    ) -> Result<Tree<Behave>, BehaveXmlError> {
+        let tag = node.tag_name().name();
+        if UNSUPPORTED_NODES.contains(&tag) {
+            return Err(BehaveXmlError::UnsupportedNode {
+                tag: tag.to_string(),
+                line: line(node),
+            });
+        }
+        let children = element_children(node)
+            .into_iter()
+            .map(|child| self.convert_node(doc, child, stack))
+            .collect::<Result<Vec<_>, _>>()?;
+        let child_count = children.len();
+        let invalid_children = || BehaveXmlError::InvalidChildren {
+            tag: tag.to_string(),
+            children: child_count,
+            line: line(node),
+        };
+        let tree = match tag {
+            "Sequence" => with_children(Behave::Sequence, children),
+            "Fallback" => with_children(Behave::Fallback, children),
+            "Inverter" => {
+                let child = single_child(children).ok_or_else(invalid_children)?;
+                with_children(Behave::Invert, [child])
+            }
+            "KeepRunningUntilFailure" => {
+                let child = single_child(children).ok_or_else(invalid_children)?;
+                with_children(Behave::While, [child])
+            }
+            "IfThenElse" | "WhileDoElse" if !(2..=3).contains(&child_count) => {
+                return Err(invalid_children());
+            }
+            "IfThenElse" | "WhileDoElse" => with_children(Behave::IfThen, children),
+            "ForceSuccess" => {
+                let child = single_child(children).ok_or_else(invalid_children)?;
+                with_children(Behave::Fallback, [child, Tree::new(Behave::AlwaysSucceed)])
+            }
+            "ForceFailure" => {
+                let child = single_child(children).ok_or_else(invalid_children)?;
+                with_children(Behave::Sequence, [child, Tree::new(Behave::AlwaysFail)])
+            }
+            "RetryUntilSuccessful" => {
+                let child = single_child(children).ok_or_else(invalid_children)?;
+                let attempts = parse_attribute::<i64>(node, "num_attempts")?;
+                if attempts == 0 {
+                    // an empty Fallback would succeed without ever running the child
+                    return Err(BehaveXmlError::InvalidAttribute {
+                        tag: tag.to_string(),
+                        attribute: "num_attempts".to_string(),
+                        value: Some(attempts.to_string()),
+                        line: line(node),
+                    });
+                }
+                retry_until_successful(child, attempts)
+            }
+            "AlwaysSuccess" | "AlwaysFailure" if !children.is_empty() => {
+                return Err(invalid_children());
+            }
+            "AlwaysSuccess" => Tree::new(Behave::AlwaysSucceed),
+            "AlwaysFailure" => Tree::new(Behave::AlwaysFail),
+            "Sleep" => {
+                let msec = parse_attribute::<f32>(node, "msec")?;
+                Tree::new(Behave::Wait(msec / 1000.0))
+            }
+            "SubTree" => {
+                let id = required_attribute(node, "ID")?;
+                self.convert_tree(doc, id, stack)?
+            }
+            _ => {
+                // a leaf: either <Action ID="name"/> / <Condition ID="name"/>, or a custom tag.
+                let name = match tag {
+                    "Action" | "Condition" => required_attribute(node, "ID")?,
+                    tag => tag,
+                };
+                if !children.is_empty() {
+                    return Err(invalid_children());
+                }
+                let Some(factory) = self.leaves.get(name) else {
+                    return Err(BehaveXmlError::UnknownLeaf {
+                        name: name.to_string(),
+                        line: line(node),
+                    });
+                };
+                let ports = node
+                    .attributes()
+                    .filter(|a| a.name() != "ID" && a.name() != "name")
+                    .map(|a| (a.name().to_string(), a.value().to_string()))
+                    .collect::<BehaveXmlPorts>();
+                let behave = factory(&ports).map_err(|reason| BehaveXmlError::InvalidLeaf {
+                    name: name.to_string(),
+                    reason,
+                    line: line(node),
+                })?;
+                Tree::new(behave)
+            }
+        };
+        Ok(tree)
+    }
+}
+
+/// Built-in BehaviorTree.CPP nodes that have no equivalent in bevy_behave.
+const UNSUPPORTED_NODES: &[&str] = &[
+    "ReactiveSequence",
+    "ReactiveFallback",
+    "SequenceWithMemory",
+    "Parallel",
+    "ParallelAll",
+    "Switch2",
+    "Switch3",
+    "Switch4",
+    "Switch5",
+    "Switch6",
+    "Repeat",
+    "Timeout",
+    "Delay",
+    "RunOnce",
+    "Precondition",
+    "ScriptCondition",
+    "Script",
+    "SetBlackboard",
+    "SubTreePlus",
+];
+
+/// Retrying n times is a `Fallback` of n copies of the child.
+/// Retrying forever is `Invert(While(Invert(child)))`: loop while the child fails.
+fn retry_until_successful(child: Tree<Behave>, attempts: i64) -> Tree<Behave> {
+    if attempts < 0 {
+        let inner = with_children(Behave::Invert, [child]);
+        let looped = with_children(Behave::While, [inner]);
+        return with_children(Behave::Invert, [looped]);
+    }
+    with_children(
+        Behave::Fallback,
+        std::iter::repeat_n(child, attempts as usize),
+    )
+}
+
+fn with_children(behave: Behave, children: impl IntoIterator<Item = Tree<Behave>>) -> Tree<Behave> {
+    let mut tree = Tree::new(behave);
+    for child in children {
+        tree.root_mut().append_subtree(child);
+    }
+    tree
+}
+
+fn single_child(children: Vec<Tree<Behave>>) -> Option<Tree<Behave>> {
+    let mut children = children.into_iter();
+    match (children.next(), children.next()) {
+        (Some(child), None) => Some(child),
+        _ => None,
+    }
+}
+
+fn parse(xml: &str) -> Result<roxmltree::Document<'_>, BehaveXmlError> {
+    roxmltree::Document::parse(xml).map_err(|e| BehaveXmlError::Parse(e.to_string()))
+}
+
+fn behavior_trees<'a, 'input>(
+    doc: &'a roxmltree::Document<'input>,
+) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+    doc.root_element()
+        .children()
+        .filter(|n| n.has_tag_name("BehaviorTree"))
+}
+
+fn element_children<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+) -> Vec<roxmltree::Node<'a, 'input>> {
+    node.children().filter(|n| n.is_element()).collect()
+}
+
+fn line(node: roxmltree::Node) -> u32 {
+    node.document().text_pos_at(node.range().start).row
+}
+
+fn required_attribute<'a>(
+    node: roxmltree::Node<'a, '_>,
+    attribute: &str,
+) -> Result<&'a str, BehaveXmlError> {
+    node.attribute(attribute)
+        .ok_or_else(|| BehaveXmlError::InvalidAttribute {
+            tag: node.tag_name().name().to_string(),
+            attribute: attribute.to_string(),
+            value: None,
+            line: line(node),
+        })
+}
+
+fn parse_attribute<T: std::str::FromStr>(
+    node: roxmltree::Node,
+    attribute: &str,
+) -> Result<T, BehaveXmlError> {
+    let value = required_attribute(node, attribute)?;
+    value.parse().map_err(|_| BehaveXmlError::InvalidAttribute {
+        tag: node.tag_name().name().to_string(),
+        attribute: attribute.to_string(),
+        value: Some(value.to_string()),
+        line: line(node),
+    })
+}
+
+/// Errors from [`BehaveXmlImporter`]. Line numbers are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BehaveXmlError {
+    /// The document isn't well-formed XML.
+    Parse(String),
+    /// There's no `main_tree_to_execute` attribute, and not exactly one `<BehaviorTree>`.
+    NoMainTree,
+    /// No `<BehaviorTree>` with this `ID` exists in the document.
+    TreeNotFound(String),
+    /// A `<SubTree>` includes itself, directly or indirectly.
+    RecursiveSubTree(String),
+    /// A built-in BehaviorTree.CPP node which bevy_behave has no equivalent for.
+    UnsupportedNode {
+        /// The tag name
+        tag: String,
+        /// Line of the node
+        line: u32,
+    },
+    /// An action or condition that hasn't been registered with the importer.
+    UnknownLeaf {
+        /// The tag name, or `ID` for `<Action>` and `<Condition>` nodes
+        name: String,
+        /// Line of the node
+        line: u32,
+    },
+    /// A registered leaf factory rejected the node's ports.
+    InvalidLeaf {
+        /// The tag name, or `ID` for `<Action>` and `<Condition>` nodes
+        name: String,
+        /// The error returned by the factory
+        reason: String,
+        /// Line of the node
+        line: u32,
+    },
+    /// A node has the wrong number of children.
+    InvalidChildren {
+        /// The tag name
+        tag: String,
+        /// The number of child elements found
+        children: usize,
+        /// Line of the node
+        line: u32,
+    },
+    /// A required attribute is missing (`value` is `None`) or couldn't be parsed.
+    InvalidAttribute {
+        /// The tag name
+        tag: String,
+        /// The attribute name
+        attribute: String,
+        /// The value, if present
+        value: Option<String>,
+        /// Line of the node
+        line: u32,
+    },
+}
+
+impl std::fmt::Display for BehaveXmlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "Invalid XML: {e}"),
+            Self::NoMainTree => write!(
+                f,
+                "No main_tree_to_execute attribute, and the document doesn't contain exactly one BehaviorTree"
+            ),
+            Self::TreeNotFound(id) => write!(f, "No BehaviorTree with ID \"{id}\""),
+            Self::RecursiveSubTree(id) => write!(f, "SubTree \"{id}\" includes itself"),
+            Self::UnsupportedNode { tag, line } => {
+                write!(f, "Line {line}: <{tag}> is not supported by bevy_behave")
+            }
+            Self::UnknownLeaf { name, line } => write!(
+                f,
+                "Line {line}: no action or condition registered for \"{name}\""
+            ),
+            Self::InvalidLeaf { name, reason, line } => {
+                write!(f, "Line {line}: invalid \"{name}\" node: {reason}")
+            }
+            Self::InvalidChildren {
+                tag,
+                children,
+                line,
+            } => write!(f, "Line {line}: <{tag}> can't have {children} children"),
+            Self::InvalidAttribute {
+                tag,
+                attribute,
+                value: None,
+                line,
+            } => write!(
+                f,
+                "Line {line}: <{tag}> is missing attribute \"{attribute}\""
+            ),
+            Self::InvalidAttribute {
+                tag,
+                attribute,
+                value: Some(value),
+                line,
+            } => write!(
+                f,
+                "Line {line}: <{tag}> has invalid {attribute}=\"{value}\""
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BehaveXmlError {}